@@ -0,0 +1,201 @@
+use std::io;
+use std::str::FromStr;
+
+use grid::generator::Generator;
+use grid::strategy::Strategy;
+
+/// How the results are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Markdown,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return match value {
+            "markdown" => Ok(Format::Markdown),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format: {}", value)),
+        };
+    }
+}
+
+/// Options of the benchmark, read from the command line.
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    pub strategies: Vec<Strategy>,
+    pub generators: Vec<Generator>,
+    /// The width and height of the square mazes, in cells.
+    pub sizes: Vec<usize>,
+    /// Each combination is played on the mazes of the seeds `0..seeds`.
+    pub seeds: u64,
+    /// The number of players of a team, each one starting on a random cell.
+    pub players: usize,
+    /// The probability to open each dead end of the generated mazes.
+    pub braid: f64,
+    /// A player gives up after `max_moves_per_cell * size * size` moves.
+    pub max_moves_per_cell: u32,
+    pub format: Format,
+    /// The file where the results are written, the standard output if `None`.
+    pub output: Option<String>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            strategies: vec![
+                Strategy::Tremaux,
+                Strategy::Random,
+                Strategy::LeftHand,
+                Strategy::RightHand,
+                Strategy::Frontier,
+            ],
+            generators: vec![
+                Generator::RecursiveBacktracking,
+                Generator::Prims,
+                Generator::Ellers,
+                Generator::GrowingTree,
+            ],
+            sizes: vec![10, 25, 50],
+            seeds: 10,
+            players: 1,
+            braid: 0.0,
+            max_moves_per_cell: 20,
+            format: Format::Markdown,
+            output: None,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Builds the configuration from the command line arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments, without the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is unknown or if its value is missing or invalid.
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> io::Result<Self> {
+        let mut config: BenchConfig = BenchConfig::default();
+
+        while let Some(option) = args.next() {
+            let value: String = args.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Missing value for option {}", option),
+                )
+            })?;
+
+            match option.as_str() {
+                "--strategies" => config.strategies = parse_list(&option, &value)?,
+                "--generators" => config.generators = parse_list(&option, &value)?,
+                "--sizes" => config.sizes = parse_list(&option, &value)?,
+                "--seeds" => config.seeds = parse_value(&option, &value)?,
+                "--players" => config.players = parse_value(&option, &value)?,
+                "--braid" => config.braid = parse_value(&option, &value)?,
+                "--max-moves-per-cell" => config.max_moves_per_cell = parse_value(&option, &value)?,
+                "--format" => config.format = parse_value(&option, &value)?,
+                "--output" => config.output = Some(value),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Unknown option {}", option),
+                    ));
+                }
+            }
+        }
+
+        if config.sizes.iter().any(|&size: &usize| size < 2) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--sizes must be at least 2, the players cannot start on the exit",
+            ));
+        }
+
+        if config.seeds == 0 || config.players == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--seeds and --players must be at least 1",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&config.braid) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--braid must be between 0 and 1",
+            ));
+        }
+
+        return Ok(config);
+    }
+}
+
+fn parse_value<T: FromStr>(option: &str, value: &str) -> io::Result<T> {
+    return value.parse::<T>().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid value '{}' for option {}", value, option),
+        )
+    });
+}
+
+/// Parses a list written as `first,second,...`.
+fn parse_list<T: FromStr>(option: &str, value: &str) -> io::Result<Vec<T>> {
+    return value
+        .split(',')
+        .map(|item: &str| parse_value(option, item.trim()))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|value: &&str| value.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let config: BenchConfig = BenchConfig::from_args(args(&[
+            "--strategies",
+            "tremaux",
+            "--generators",
+            "prims,ellers",
+            "--sizes",
+            "10, 20",
+            "--seeds",
+            "5",
+            "--format",
+            "csv",
+        ]))
+        .unwrap();
+
+        assert_eq!(config.strategies, vec![Strategy::Tremaux]);
+        assert_eq!(config.generators, vec![Generator::Prims, Generator::Ellers]);
+        assert_eq!(config.sizes, vec![10, 20]);
+        assert_eq!(config.seeds, 5);
+        assert_eq!(config.players, 1);
+        assert_eq!(config.format, Format::Csv);
+        assert_eq!(config.output, None);
+    }
+
+    #[test]
+    fn test_from_args_invalid() {
+        assert!(BenchConfig::from_args(args(&["--seeds"])).is_err());
+        assert!(BenchConfig::from_args(args(&["--strategies", "tremaux,dfs"])).is_err());
+        assert!(BenchConfig::from_args(args(&["--sizes", "1,10"])).is_err());
+        assert!(BenchConfig::from_args(args(&["--players", "0"])).is_err());
+        assert!(BenchConfig::from_args(args(&["--format", "html"])).is_err());
+        assert!(BenchConfig::from_args(args(&["--unknown", "1"])).is_err());
+    }
+}
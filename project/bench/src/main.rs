@@ -0,0 +1,161 @@
+mod config;
+mod report;
+
+use config::{BenchConfig, Format};
+use grid::generator::{generate, Generator, GeneratorOptions};
+use grid::maze::{Cell, Maze};
+use grid::simulator::{simulate, PlayerOutcome};
+use grid::strategy::Strategy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use report::{to_csv, to_markdown, Row, Stats};
+use shared::types::log::log::Log;
+use shared::{log_error, log_info};
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Instant;
+
+fn main() {
+    if let Err(e) = Log::init_from_env() {
+        log_error!("Failed to configure the logs: {}", e);
+    }
+
+    let config: BenchConfig = match BenchConfig::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            log_error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let start: Instant = Instant::now();
+    let mut rows: Vec<Row> = Vec::new();
+    let mut failed: bool = false;
+    for &strategy in &config.strategies {
+        for &generator in &config.generators {
+            for &size in &config.sizes {
+                let (outcomes, failed_seeds) = play_seeds(&config, strategy, generator, size);
+                if !failed_seeds.is_empty() {
+                    // The statistics of the combination leave out the games which panicked.
+                    log_error!(
+                        "The games of {} on {} mazes of size {} panicked for the seeds {:?}",
+                        strategy.name(),
+                        generator.name(),
+                        size,
+                        failed_seeds
+                    );
+                    failed = true;
+                }
+                rows.push(Row {
+                    strategy: strategy.name(),
+                    generator: generator.name(),
+                    size,
+                    stats: Stats::new(&outcomes),
+                });
+            }
+        }
+    }
+
+    let results: String = match config.format {
+        Format::Markdown => to_markdown(&rows),
+        Format::Csv => to_csv(&rows),
+    };
+    match &config.output {
+        Some(path) => {
+            if let Err(e) = fs::write(path, results) {
+                log_error!("Failed to write the results to {}: {}", path, e);
+                std::process::exit(1);
+            }
+            log_info!(
+                "{} combinations played in {:.1} s, results written to {}",
+                rows.len(),
+                start.elapsed().as_secs_f64(),
+                path
+            );
+        }
+        None => print!("{}", results),
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// The outcomes of the players of some games, then the seeds of the games which panicked.
+type Games = (Vec<PlayerOutcome>, Vec<u64>);
+
+/// Plays the games of every seed of a combination, the seeds being shared between the cores.
+///
+/// # Returns
+///
+/// The outcomes of the players, then the seeds whose game panicked, in increasing order.
+fn play_seeds(
+    config: &BenchConfig,
+    strategy: Strategy,
+    generator: Generator,
+    size: usize,
+) -> Games {
+    let threads: u64 = thread::available_parallelism().map_or(1, |count| count.get() as u64);
+    let (outcomes, mut failed_seeds) = thread::scope(|scope| {
+        let handles: Vec<thread::ScopedJoinHandle<'_, Games>> = (0..threads)
+            .map(|first_seed: u64| {
+                scope.spawn(move || {
+                    let mut outcomes: Vec<PlayerOutcome> = Vec::new();
+                    let mut failed_seeds: Vec<u64> = Vec::new();
+                    for seed in (first_seed..config.seeds).step_by(threads as usize) {
+                        // A panic only loses the game of its seed, the thread plays the next ones.
+                        match panic::catch_unwind(AssertUnwindSafe(|| {
+                            play_game(config, strategy, generator, size, seed)
+                        })) {
+                            Ok(game) => outcomes.extend(game),
+                            Err(_) => failed_seeds.push(seed),
+                        }
+                    }
+                    (outcomes, failed_seeds)
+                })
+            })
+            .collect();
+        let mut outcomes: Vec<PlayerOutcome> = Vec::new();
+        let mut failed_seeds: Vec<u64> = Vec::new();
+        for handle in handles {
+            let (game_outcomes, game_failed_seeds) = handle
+                .join()
+                .expect("the panics of the games are caught by their thread");
+            outcomes.extend(game_outcomes);
+            failed_seeds.extend(game_failed_seeds);
+        }
+        (outcomes, failed_seeds)
+    });
+    failed_seeds.sort_unstable();
+    return (outcomes, failed_seeds);
+}
+
+/// Plays a game on the maze of a seed, the players starting on random cells of that seed.
+fn play_game(
+    config: &BenchConfig,
+    strategy: Strategy,
+    generator: Generator,
+    size: usize,
+    seed: u64,
+) -> Vec<PlayerOutcome> {
+    let options: GeneratorOptions = GeneratorOptions {
+        generator,
+        seed,
+        braid: config.braid,
+    };
+    let maze: Maze = generate(size, size, &options);
+
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    let starts: Vec<Cell> = (0..config.players)
+        .map(|_| loop {
+            let start: Cell = (rng.gen_range(0..size), rng.gen_range(0..size));
+            if start != maze.goal {
+                break start;
+            }
+        })
+        .collect();
+
+    let max_moves: u32 = config.max_moves_per_cell * (size * size) as u32;
+    return simulate(&maze, &starts, strategy, max_moves, seed);
+}
@@ -0,0 +1,161 @@
+use grid::simulator::PlayerOutcome;
+
+/// The statistics of the games of a combination of strategy, generator and size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// The number of players which played, one per start cell of each game.
+    pub runs: usize,
+    /// The share of the players which did not find the exit, between 0 and 1.
+    pub failure_rate: f64,
+    /// The moves of the players which found the exit. `0` when none did.
+    pub mean: f64,
+    pub median: f64,
+    pub p95: f64,
+}
+
+impl Stats {
+    pub fn new(outcomes: &[PlayerOutcome]) -> Stats {
+        let mut moves: Vec<u32> = outcomes
+            .iter()
+            .filter(|outcome: &&PlayerOutcome| outcome.found_exit)
+            .map(|outcome: &PlayerOutcome| outcome.moves)
+            .collect();
+        moves.sort_unstable();
+
+        let failures: usize = outcomes.len() - moves.len();
+        return Stats {
+            runs: outcomes.len(),
+            failure_rate: if outcomes.is_empty() {
+                0.0
+            } else {
+                failures as f64 / outcomes.len() as f64
+            },
+            mean: if moves.is_empty() {
+                0.0
+            } else {
+                moves.iter().map(|&count: &u32| count as f64).sum::<f64>() / moves.len() as f64
+            },
+            median: median(&moves),
+            p95: percentile(&moves, 0.95),
+        };
+    }
+}
+
+/// A line of the results.
+pub struct Row {
+    pub strategy: &'static str,
+    pub generator: &'static str,
+    pub size: usize,
+    pub stats: Stats,
+}
+
+/// Writes the results as a Markdown table.
+pub fn to_markdown(rows: &[Row]) -> String {
+    let mut table: String = String::from(
+        "| strategy | generator | size | runs | mean | median | p95 | failure rate |\n\
+         |---|---|---|---:|---:|---:|---:|---:|\n",
+    );
+    for row in rows {
+        table.push_str(&format!(
+            "| {} | {} | {}x{} | {} | {:.1} | {:.1} | {:.1} | {:.1}% |\n",
+            row.strategy,
+            row.generator,
+            row.size,
+            row.size,
+            row.stats.runs,
+            row.stats.mean,
+            row.stats.median,
+            row.stats.p95,
+            row.stats.failure_rate * 100.0
+        ));
+    }
+    return table;
+}
+
+/// Writes the results as CSV, with a header line.
+pub fn to_csv(rows: &[Row]) -> String {
+    let mut csv: String =
+        String::from("strategy,generator,size,runs,mean,median,p95,failure_rate\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{:.2},{:.2},{:.2},{:.4}\n",
+            row.strategy,
+            row.generator,
+            row.size,
+            row.stats.runs,
+            row.stats.mean,
+            row.stats.median,
+            row.stats.p95,
+            row.stats.failure_rate
+        ));
+    }
+    return csv;
+}
+
+/// Returns the median of sorted values, `0` if there are none.
+fn median(sorted: &[u32]) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let middle: usize = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        return (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0;
+    }
+    return sorted[middle] as f64;
+}
+
+/// Returns the nearest-rank percentile of sorted values, `0` if there are none.
+fn percentile(sorted: &[u32], ratio: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank: usize = (ratio * sorted.len() as f64).ceil() as usize;
+    return sorted[rank.clamp(1, sorted.len()) - 1] as f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(moves: u32, found_exit: bool) -> PlayerOutcome {
+        return PlayerOutcome {
+            moves,
+            wall_collisions: 0,
+            found_exit,
+        };
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut outcomes: Vec<PlayerOutcome> = (1..=20).map(|moves| outcome(moves, true)).collect();
+        outcomes.push(outcome(500, false));
+        let stats: Stats = Stats::new(&outcomes);
+
+        assert_eq!(stats.runs, 21);
+        assert_eq!(stats.failure_rate, 1.0 / 21.0);
+        assert_eq!(stats.mean, 10.5);
+        assert_eq!(stats.median, 10.5);
+        assert_eq!(stats.p95, 19.0);
+        assert_eq!(Stats::new(&[outcome(7, false)]).mean, 0.0);
+    }
+
+    #[test]
+    fn test_tables() {
+        let rows: Vec<Row> = vec![Row {
+            strategy: "tremaux",
+            generator: "prims",
+            size: 10,
+            stats: Stats::new(&[outcome(10, true), outcome(30, true), outcome(0, false)]),
+        }];
+
+        assert_eq!(
+            to_markdown(&rows).lines().nth(2),
+            Some("| tremaux | prims | 10x10 | 3 | 20.0 | 20.0 | 30.0 | 33.3% |")
+        );
+        assert_eq!(
+            to_csv(&rows),
+            "strategy,generator,size,runs,mean,median,p95,failure_rate\n\
+             tremaux,prims,10,3,20.00,20.00,30.00,0.3333\n"
+        );
+    }
+}
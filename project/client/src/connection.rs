@@ -0,0 +1,185 @@
+use std::io;
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use shared::types::error::RegistrationError;
+use shared::types::hint::Hint;
+use shared::utils::{connect_to_server, subscribe_player, SessionStart};
+
+/// How many times a player tries to get its connection back before giving up.
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// The wait before the first attempt, doubled after each failed one.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+
+/// The longest wait between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// What a player needs to open its connection again and resume its session after losing it.
+#[derive(Debug, Clone)]
+pub struct Reconnection {
+    pub server_address: String,
+    pub registration_token: String,
+    pub attempts: u32,
+    pub initial_backoff: Duration,
+}
+
+impl Reconnection {
+    pub fn new(server_address: &str, registration_token: &str) -> Reconnection {
+        return Reconnection {
+            server_address: String::from(server_address),
+            registration_token: String::from(registration_token),
+            attempts: RECONNECT_ATTEMPTS,
+            initial_backoff: INITIAL_BACKOFF,
+        };
+    }
+
+    /// Connects to the server again and subscribes the player with the registration token of its team,
+    /// retrying with an exponential backoff while the failures look transient.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_name` - The name the player has subscribed with.
+    /// * `is_restart` - Whether the player closed its old connection itself. The server refuses the
+    ///   subscription as `AlreadyRegistered` until it sees that connection closed, so the refusal is retried.
+    ///
+    /// # Returns
+    ///
+    /// The new stream and what the server sends when the session is resumed: the radar view, or the
+    /// challenge running when the connection was lost, then the hints sent before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once the attempts are exhausted, or the first one which is not transient:
+    /// a server which refuses the subscription does not support resuming sessions.
+    pub fn resume(
+        &self,
+        player_name: &str,
+        is_restart: bool,
+    ) -> io::Result<(TcpStream, SessionStart, Vec<Hint>)> {
+        let mut backoff: Duration = self.initial_backoff;
+        let mut attempt: u32 = 1;
+        loop {
+            thread::sleep(backoff);
+            let result: io::Result<(TcpStream, SessionStart, Vec<Hint>)> =
+                connect_to_server(&self.server_address).and_then(
+                    |mut stream: TcpStream| -> io::Result<(TcpStream, SessionStart, Vec<Hint>)> {
+                        let (session_start, hints) =
+                            subscribe_player(&mut stream, &self.registration_token, player_name)?;
+                        return Ok((stream, session_start, hints));
+                    },
+                );
+            match result {
+                Ok(session) => return Ok(session),
+                Err(e)
+                    if (is_transient(&e) || (is_restart && is_already_registered(&e)))
+                        && attempt < self.attempts =>
+                {
+                    log_warning!(
+                        "{}: Reconnection attempt {}/{} has failed: {}",
+                        player_name,
+                        attempt,
+                        self.attempts,
+                        e
+                    );
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Tells if an I/O error comes from a lost or refused connection, which a new connection may fix.
+pub fn is_transient(error: &io::Error) -> bool {
+    return matches!(
+        error.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::UnexpectedEof
+    );
+}
+
+/// Tells if the server refused a subscription because the player is still connected.
+pub fn is_already_registered(error: &io::Error) -> bool {
+    return matches!(
+        error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<RegistrationError>()),
+        Some(RegistrationError::AlreadyRegistered)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::types::message::{GameMessage, SubscribePlayerResult};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_resume_gives_up() {
+        // Nothing listens on the port any more.
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let mut reconnection: Reconnection = Reconnection::new(&address, "token");
+        reconnection.attempts = 3;
+        reconnection.initial_backoff = Duration::from_millis(1);
+        let error: io::Error = reconnection.resume("Player 1", false).unwrap_err();
+        assert!(is_transient(&error));
+
+        assert!(!is_transient(&io::Error::other(
+            "Player registration failed"
+        )));
+    }
+
+    #[test]
+    fn test_restart_waits_for_the_old_connection() {
+        // The server sees the old connection closed after the first subscription only.
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = listener.local_addr().unwrap().to_string();
+        let server: thread::JoinHandle<()> = thread::spawn(move || {
+            for is_closed in [false, true] {
+                let (mut stream, _) = listener.accept().unwrap();
+                GameMessage::receive(&mut stream).unwrap();
+                if !is_closed {
+                    GameMessage::SubscribePlayerResult(SubscribePlayerResult::Err(
+                        RegistrationError::AlreadyRegistered,
+                    ))
+                    .send(&mut stream)
+                    .unwrap();
+                    continue;
+                }
+                GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)
+                    .send(&mut stream)
+                    .unwrap();
+                GameMessage::Hint(Hint::Secret(7))
+                    .send(&mut stream)
+                    .unwrap();
+                GameMessage::RadarView(String::from("radar"))
+                    .send(&mut stream)
+                    .unwrap();
+            }
+        });
+
+        let mut reconnection: Reconnection = Reconnection::new(&address, "token");
+        reconnection.initial_backoff = Duration::from_millis(1);
+        let (_, session_start, hints) = reconnection.resume("Player 1", true).unwrap();
+        assert!(matches!(session_start, SessionStart::RadarView(radar) if radar == "radar"));
+        // The hint sent with the view is kept for the player.
+        assert!(matches!(hints.as_slice(), [Hint::Secret(7)]));
+        server.join().unwrap();
+
+        // Without a restart, the player may be connected elsewhere: the refusal is final.
+        let error: io::Error = io::Error::other(RegistrationError::AlreadyRegistered);
+        assert!(is_already_registered(&error));
+        assert!(!is_transient(&error));
+    }
+}
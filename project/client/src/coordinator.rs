@@ -0,0 +1,362 @@
+use grid::map::Map;
+use grid::store::Position;
+use shared::types::cardinal_direction::CardinalDirection;
+
+/// The moves a player accepts to spend to head straight to the exit given by its compass, once a teammate
+/// has found the exit. Much more than `grid::map::COMPASS_WEIGHT`: the whole team converges. It only
+/// matters once the player has a compass hint: without one, it keeps exploring the nearest frontier.
+const TEAM_GOAL_COMPASS_WEIGHT: f32 = 12.0;
+
+/// What the coordinator knows of a player.
+#[derive(Debug, Clone, Default)]
+struct Member {
+    /// The distance to the nearest frontier cell of each sector, see `Map::sector_costs`.
+    bids: Vec<(CardinalDirection, u32)>,
+    /// Cleared while the player is blocked by an opponent or busy with a challenge.
+    is_available: bool,
+    /// Set when the player has lost its connection for good.
+    is_disconnected: bool,
+    sector: Option<CardinalDirection>,
+    /// What to add to a position of the map of the player to get the same position on the map of the
+    /// anchor, once the player has found where it stands on it.
+    offset: Option<Position>,
+}
+
+/// Splits the exploration of the maze between the players of a team, so that they do not chase the same
+/// frontier cells.
+///
+/// The maps of the players do not share an origin: the server never tells where a player is. So the
+/// first player to coordinate is the anchor, and the others explore on their own until they find where
+/// they stand on its map. The coordinator then gives each aligned player a sector, a direction from the
+/// spawn of the anchor, with a greedy auction on the distances to the frontier cells: the cheapest bid
+/// wins, each player and each sector being taken once while possible. The sectors are auctioned again
+/// whenever a bid changes or a player becomes blocked or available. Once a player has found the exit, the others look for where they stand on its map: a player
+/// which finds it learns that map and routes to the exit. Until then, it follows its compass.
+#[derive(Clone)]
+pub struct Coordinator {
+    members: Vec<Member>,
+    /// The player which found the exit, if any.
+    pub goal_found_by: Option<usize>,
+    /// The last map shared by the player which found the exit.
+    goal_map: Option<Map>,
+    /// The player whose map the others align with, the first one to coordinate.
+    anchor: Option<usize>,
+    /// The last map shared by the anchor.
+    anchor_map: Option<Map>,
+    /// The cell all the sectors start from, the spawn of the anchor on its map.
+    origin: Option<Position>,
+}
+
+impl Coordinator {
+    pub fn new(players: usize) -> Coordinator {
+        return Coordinator {
+            members: vec![
+                Member {
+                    is_available: true,
+                    ..Member::default()
+                };
+                players
+            ],
+            goal_found_by: None,
+            goal_map: None,
+            anchor: None,
+            anchor_map: None,
+            origin: None,
+        };
+    }
+
+    /// Records the bids of a player and assigns the sectors again if they changed.
+    pub fn bid(&mut self, player: usize, bids: Vec<(CardinalDirection, u32)>) {
+        if self.members[player].bids != bids {
+            self.members[player].bids = bids;
+            self.assign();
+        }
+    }
+
+    /// Marks a player as able to explore or not, and assigns the sectors again if that changed.
+    pub fn set_available(&mut self, player: usize, is_available: bool) {
+        if self.members[player].is_available != is_available {
+            self.members[player].is_available = is_available;
+            self.assign();
+        }
+    }
+
+    /// Takes a player which lost its connection for good out of the auctions, its sector going to the others.
+    pub fn disconnect(&mut self, player: usize) {
+        log_warning!(
+            "Player {} has left the game, its sector is auctioned again",
+            player + 1
+        );
+        self.members[player].is_disconnected = true;
+        self.assign();
+    }
+
+    pub fn sector(&self, player: usize) -> Option<CardinalDirection> {
+        return self.members[player].sector;
+    }
+
+    /// Shares the map of a player with the team before it chooses its move: reports the exit if the player
+    /// has found it, aligns its map with the map of the anchor, records its bids, then directs its map to
+    /// its sector or to the exit.
+    pub fn coordinate(&mut self, player: usize, map: &mut Map) {
+        if self.goal_found_by.is_none() && map.known_goal().is_some() {
+            log_info!(
+                "Player {} has found the exit, the team heads to it",
+                player + 1
+            );
+            self.goal_found_by = Some(player);
+        }
+        if let Some(finder) = self.goal_found_by {
+            if finder == player {
+                self.goal_map = Some(map.clone());
+            } else if let (None, Some(goal_map)) = (map.known_goal(), &self.goal_map) {
+                share_goal_map(player, goal_map, map);
+            }
+            map.sector = None;
+            map.compass_weight = TEAM_GOAL_COMPASS_WEIGHT;
+            return;
+        }
+
+        if !self.align(player, map) {
+            // Its sectors would not match those of the others: it explores on its own until then.
+            return;
+        }
+        self.bid(player, map.sector_costs());
+        let sector: Option<CardinalDirection> = self.sector(player);
+        if map.sector != sector {
+            log_debug!("Player {} explores the sector {:?}", player + 1, sector);
+            map.sector = sector;
+            map.plan = None;
+        }
+    }
+
+    /// Looks for where a player stands on the map of the anchor, unless it already knows, and makes the
+    /// sectors of its map start from the origin of the team.
+    ///
+    /// # Returns
+    ///
+    /// Whether the map of the player is aligned with the map of the anchor.
+    fn align(&mut self, player: usize, map: &mut Map) -> bool {
+        let anchor: usize = *self.anchor.get_or_insert(player);
+        if anchor == player {
+            self.members[player].offset = Some((0, 0));
+            self.origin.get_or_insert(map.spawn_position);
+            self.anchor_map = Some(map.clone());
+        } else if self.members[player].offset.is_none() {
+            let position: Position = match self
+                .anchor_map
+                .as_ref()
+                .and_then(|anchor_map: &Map| anchor_map.locate(map, None))
+            {
+                Some(position) => position,
+                None => return false,
+            };
+            log_info!(
+                "Player {} has found where it stands on the map of player {}, it joins the auction",
+                player + 1,
+                anchor + 1
+            );
+            self.members[player].offset = Some((
+                position.0 - map.player_position.0,
+                position.1 - map.player_position.1,
+            ));
+        }
+
+        let (offset, origin) = match (self.members[player].offset, self.origin) {
+            (Some(offset), Some(origin)) => (offset, origin),
+            _ => return false,
+        };
+        map.sector_origin = Some((origin.0 - offset.0, origin.1 - offset.1));
+        return true;
+    }
+
+    /// Runs the greedy auction: the available players get the sectors of their cheapest bids, one sector
+    /// per player. When there are more players than sectors, the last ones share their cheapest sector.
+    fn assign(&mut self) {
+        let mut bids: Vec<(u32, usize, usize, CardinalDirection)> = Vec::new();
+        for (player, member) in self.members.iter().enumerate() {
+            if member.is_available && !member.is_disconnected {
+                for (rank, &(sector, cost)) in member.bids.iter().enumerate() {
+                    bids.push((cost, player, rank, sector));
+                }
+            }
+        }
+        bids.sort_by_key(|&(cost, player, rank, _)| (cost, player, rank));
+
+        for member in &mut self.members {
+            member.sector = None;
+        }
+        let mut taken: Vec<CardinalDirection> = Vec::new();
+        for &(_, player, _, sector) in &bids {
+            if self.members[player].sector.is_none() && !taken.contains(&sector) {
+                self.members[player].sector = Some(sector);
+                taken.push(sector);
+            }
+        }
+        for &(_, player, _, sector) in &bids {
+            if self.members[player].sector.is_none() {
+                self.members[player].sector = Some(sector);
+            }
+        }
+    }
+}
+
+/// Teaches a player the map of the player which found the exit, once it knows where it stands on it.
+/// The positions of the two maps differ by the offset between the spawns, which the server never tells.
+fn share_goal_map(player: usize, goal_map: &Map, map: &mut Map) {
+    let position: Position = match goal_map.locate(map, None) {
+        Some(position) => position,
+        None => return,
+    };
+    log_info!(
+        "Player {} has found where it stands on the map of the exit, it heads to it",
+        player + 1
+    );
+    map.learn_from(
+        goal_map,
+        (
+            map.player_position.0 - position.0,
+            map.player_position.1 - position.1,
+        ),
+    );
+    map.plan = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grid::plan::Plan;
+    use shared::types::action::RelativeDirection;
+    use shared::utils::string_to_strings;
+
+    #[test]
+    fn test_auction() {
+        let mut coordinator: Coordinator = Coordinator::new(3);
+        coordinator.bid(
+            0,
+            vec![(CardinalDirection::North, 2), (CardinalDirection::East, 5)],
+        );
+        coordinator.bid(
+            1,
+            vec![(CardinalDirection::North, 1), (CardinalDirection::South, 9)],
+        );
+        coordinator.bid(2, vec![(CardinalDirection::North, 3)]);
+        // The cheapest bid wins North, the next player gets its second choice and the last one shares.
+        assert_eq!(coordinator.sector(1), Some(CardinalDirection::North));
+        assert_eq!(coordinator.sector(0), Some(CardinalDirection::East));
+        assert_eq!(coordinator.sector(2), Some(CardinalDirection::North));
+
+        // A blocked player gives its sector back.
+        coordinator.set_available(1, false);
+        assert_eq!(coordinator.sector(1), None);
+        assert_eq!(coordinator.sector(0), Some(CardinalDirection::North));
+        assert_eq!(coordinator.sector(2), Some(CardinalDirection::North));
+        coordinator.set_available(1, true);
+        assert_eq!(coordinator.sector(1), Some(CardinalDirection::North));
+
+        // A disconnected player never gets a sector again.
+        coordinator.disconnect(1);
+        coordinator.set_available(1, true);
+        assert_eq!(coordinator.sector(1), None);
+        assert_eq!(coordinator.sector(0), Some(CardinalDirection::North));
+    }
+
+    #[test]
+    fn test_sectors_wait_for_a_shared_origin() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("|   |     |"),
+            string_to_strings("• •-• •-• •"),
+            string_to_strings("|   |   | |"),
+            string_to_strings("•-• • •-• •"),
+            string_to_strings("|     |   |"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        // The anchor does not know the East side of its top right cell yet.
+        let mut anchor_grid: Vec<Vec<String>> = grid.clone();
+        anchor_grid[1][10] = String::from("#");
+        let mut anchor: Map = Map::new(&anchor_grid, CardinalDirection::North);
+        let mut coordinator: Coordinator = Coordinator::new(3);
+        coordinator.coordinate(0, &mut anchor);
+        assert_eq!(anchor.sector_origin, Some((3, 5)));
+        assert!(anchor.sector.is_some());
+
+        // A player which does not know where it stands on the map of the anchor explores on its own.
+        let mut lost: Map = Map::new(
+            &[
+                string_to_strings("•-•-•"),
+                string_to_strings("|   |"),
+                string_to_strings("•-•-•"),
+            ],
+            CardinalDirection::North,
+        );
+        coordinator.coordinate(1, &mut lost);
+        assert_eq!(lost.sector_origin, None);
+        assert_eq!(lost.sector, None);
+
+        // The other player knows the left part of the maze only, its map has another origin.
+        let left_part: Vec<Vec<String>> = grid
+            .iter()
+            .map(|row: &Vec<String>| row[..7].to_vec())
+            .collect();
+        let mut other: Map = Map::new_at(&left_part, (10, 20), CardinalDirection::North);
+        coordinator.coordinate(2, &mut other);
+        // The spawn of the anchor, on the map of the player.
+        assert_eq!(other.sector_origin, Some((13, 25)));
+        assert!(other.sector.is_some());
+    }
+
+    #[test]
+    fn test_goal_found() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("#  G      #"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let mut finder: Map = Map::new(&grid, CardinalDirection::North);
+        let mut grid_without_goal: Vec<Vec<String>> = grid.clone();
+        grid_without_goal[1][3] = String::from(" ");
+        let mut other: Map = Map::new(&grid_without_goal, CardinalDirection::North);
+
+        let mut coordinator: Coordinator = Coordinator::new(2);
+        coordinator.coordinate(1, &mut other);
+        assert_eq!(other.sector, Some(CardinalDirection::East));
+
+        coordinator.coordinate(0, &mut finder);
+        coordinator.coordinate(1, &mut other);
+        assert_eq!(coordinator.goal_found_by, Some(0));
+        assert_eq!(other.sector, None);
+        assert_eq!(other.compass_weight, TEAM_GOAL_COMPASS_WEIGHT);
+    }
+
+    #[test]
+    fn test_goal_map_shared() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("|   |     |"),
+            string_to_strings("• •-• •-• •"),
+            string_to_strings("|   |   | |"),
+            string_to_strings("•-• • •-• •"),
+            string_to_strings("|     |  G|"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let mut finder: Map = Map::new(&grid, CardinalDirection::North);
+        // The other player knows the left part of the maze only, its map has another origin.
+        let left_part: Vec<Vec<String>> = grid
+            .iter()
+            .map(|row: &Vec<String>| row[..7].to_vec())
+            .collect();
+        let mut other: Map = Map::new_at(&left_part, (10, 20), CardinalDirection::North);
+        assert_eq!(other.player_position, (13, 23));
+
+        let mut coordinator: Coordinator = Coordinator::new(2);
+        coordinator.coordinate(0, &mut finder);
+        coordinator.coordinate(1, &mut other);
+        assert_eq!(other.known_goal(), Some((15, 29)));
+        assert_eq!(
+            other.next_move_frontier(),
+            Some((RelativeDirection::Back, CardinalDirection::South))
+        );
+        assert_eq!(other.plan.map(|plan: Plan| plan.target), Some((15, 29)));
+    }
+}
@@ -1,26 +1,67 @@
 #[macro_use]
 extern crate shared;
+pub mod connection;
+pub mod coordinator;
+pub mod metrics;
 pub mod player;
+pub mod supervisor;
 pub mod team;
 
 use std::env;
 use std::io;
+use std::process::ExitCode;
 use std::sync::{Arc, Condvar, Mutex};
-use std::thread;
 
-use player::TurnState;
+use connection::Reconnection;
+use coordinator::Coordinator;
+use grid::map::{HintPolicy, MONSTER_GROUP_SIZE};
+use grid::renderer::{Renderer, TeamView};
+use grid::snapshot::load_map;
+use grid::strategy::Strategy;
+use metrics::{PlayerMetrics, TeamReport};
+use player::{map_file_path, TurnState};
+use shared::types::log::log::Log;
+use supervisor::{PlayerOutcome, Supervisor};
 use team::Team;
 
 const PLAYERS_NUMBER: usize = 3;
 
-fn main() -> io::Result<()> {
+/// Exits with 0 when every player has found the exit, 1 when the team has not finished and 2 when the
+/// worker could not play.
+fn main() -> ExitCode {
+    return match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            log_error!("The worker has stopped: {}", e);
+            ExitCode::from(2)
+        }
+    };
+}
+
+/// Plays the game with a team.
+///
+/// # Returns
+///
+/// Whether every player of the team has found the exit.
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, the team cannot register or the report cannot be
+/// written.
+fn run() -> io::Result<bool> {
     // Enables backtrace in case of panic.
     env::set_var("RUST_BACKTRACE", "full");
 
+    // Configures the logs from the LOG_LEVEL and LOG_JSON_FILE environment variables.
+    Log::init_from_env()?;
+
     // Parses command line arguments.
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        log_error!("Usage: worker <server_address>");
+        log_error!(
+            "Usage: worker <server_address> [--report <path>] [--render] [--export-maps <directory>] [--save-maps <directory>] [--load-maps <directory>] [--hint-policy ignore|opportunistic|always] [--strategy tremaux|random|left-hand|right-hand|frontier] [--coordinate] [--restarts <count>] [--monster-group-size <count>]"
+        );
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             "Server address required",
@@ -29,6 +70,68 @@ fn main() -> io::Result<()> {
 
     // Stores the server address.
     let server_address: &String = &args[1];
+    // Stores the path where the end of game report is written as JSON, if any.
+    let mut report_path: Option<&String> = None;
+    // Draws the map of each player in the terminal after its turns.
+    let mut render: bool = false;
+    // Exports the map of each player as a PNG in this directory at the end of the game.
+    let mut export_directory: Option<&String> = None;
+    // Saves the map of each player in this directory at the end of the game.
+    let mut save_directory: Option<&String> = None;
+    // Starts each player from the map saved in this directory, when there is one.
+    let mut load_directory: Option<&String> = None;
+    // Whether the players walk to the hint cells they see.
+    let mut hint_policy: HintPolicy = HintPolicy::Opportunistic;
+    // How the players choose their moves.
+    let mut strategy: Strategy = Strategy::Tremaux;
+    // Splits the exploration between the players.
+    let mut coordinate: bool = false;
+    // How many times a failed player is started again with its map.
+    let mut max_restarts: u32 = 0;
+    // How many teammates are needed next to a monster to defeat it, as configured on the server.
+    let mut monster_group_size: u32 = MONSTER_GROUP_SIZE;
+    let mut options = args.iter().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--report" => report_path = options.next(),
+            "--render" => render = true,
+            "--coordinate" => coordinate = true,
+            "--export-maps" => export_directory = options.next(),
+            "--save-maps" => save_directory = options.next(),
+            "--load-maps" => load_directory = options.next(),
+            "--hint-policy" => match options.next().map(|value: &String| value.parse()) {
+                Some(Ok(policy)) => hint_policy = policy,
+                Some(Err(e)) => log_warning!("{}", e),
+                None => log_warning!("Missing value for option --hint-policy"),
+            },
+            "--restarts" => match options.next().map(|value: &String| value.parse::<u32>()) {
+                Some(Ok(value)) => max_restarts = value,
+                Some(Err(e)) => log_warning!("Invalid value for option --restarts: {}", e),
+                None => log_warning!("Missing value for option --restarts"),
+            },
+            "--monster-group-size" => {
+                match options.next().map(|value: &String| value.parse::<u32>()) {
+                    Some(Ok(value)) if 0 < value => monster_group_size = value,
+                    Some(Ok(_)) => log_warning!("--monster-group-size must be at least 1"),
+                    Some(Err(e)) => {
+                        log_warning!("Invalid value for option --monster-group-size: {}", e)
+                    }
+                    None => log_warning!("Missing value for option --monster-group-size"),
+                }
+            }
+            "--strategy" => match options.next().map(|value: &String| value.parse()) {
+                Some(Ok(value)) => strategy = value,
+                Some(Err(e)) => log_warning!("{}", e),
+                None => log_warning!("Missing value for option --strategy"),
+            },
+            _ => log_warning!("Unknown option: {}", option),
+        }
+    }
+    // Only the frontier strategy explores by sector and routes to the exit shared by the team.
+    if coordinate && strategy != Strategy::Frontier {
+        log_warning!("--coordinate requires --strategy frontier, the players explore on their own");
+        coordinate = false;
+    }
 
     // Registers the team.
     let mut team: Team = Team::register(server_address, &String::from("Team 1"), PLAYERS_NUMBER)?;
@@ -44,124 +147,83 @@ fn main() -> io::Result<()> {
             current: 0,
             game_over: false,
             players_finished: 0,
+            left: Vec::new(),
         }),
         Condvar::new(),
     ));
 
-    // Creates an empty vector to store the players threads.
-    let mut threads: Vec<thread::JoinHandle<Result<(), io::Error>>> =
-        Vec::with_capacity(team.players.len());
-    // Fills the vector with the players threads.
-    for (player_id, player) in team.players.into_iter().enumerate() {
+    let coordinator: Option<Arc<Mutex<Coordinator>>> =
+        coordinate.then(|| Arc::new(Mutex::new(Coordinator::new(team.players.len()))));
+
+    // The players draw their maps in a single frame.
+    let team_view: Option<Arc<Mutex<TeamView>>> = render.then(|| {
+        Arc::new(Mutex::new(TeamView::new(
+            Renderer::new(true),
+            team.players.len(),
+        )))
+    });
+
+    // Runs each player in its own thread and collects how it ended.
+    let mut supervisor: Supervisor = Supervisor::new(max_restarts);
+    for (player_id, mut player) in team.players.into_iter().enumerate() {
+        player.team_view = team_view.clone();
+        player.export_directory = export_directory.cloned();
+        player.save_directory = save_directory.cloned();
+        if let Some(directory) = load_directory {
+            let path: String = map_file_path(directory, &player.name, "json");
+            match load_map(&path) {
+                Ok(mut map) => {
+                    // The new game may not start where the saved one did: the map is anchored on its
+                    // first radar view.
+                    if map.restart(&player.map) {
+                        log_info!("{} starts from the map saved in {}", player.name, path);
+                        player.cardinal_direction = map.current_cardinal_direction;
+                        player.map = map;
+                    } else {
+                        log_warning!(
+                            "{} cannot place its first view on the map saved in {}, it starts a new one",
+                            player.name,
+                            path
+                        );
+                    }
+                }
+                Err(e) => log_warning!("{} cannot load {}: {}", player.name, path, e),
+            }
+        }
+        player.map.hint_policy = hint_policy;
+        player.map.monster_group_size = monster_group_size;
+        player.strategy = strategy;
+        player.coordinator = coordinator.clone();
+        player.reconnection = Some(Reconnection::new(server_address, &team.registration_token));
         // Clones the shared turn state to give a reference to each player. The reference is moved to the player thread to allow each player to access the shared state.
         // Clone here is not a traditional clone, it is a reference count incrementation to allow multiple ownership of the same data instead of copying it.
-        let turn_state: Arc<(Mutex<TurnState>, Condvar)> = Arc::clone(&turn_state);
-
-        // Creates a thread for each player.
-        let thread: thread::JoinHandle<Result<(), io::Error>> =
-        // The `move` keyword is used to move ownership of the variables to the thread. This is necessary because the thread may outlive the current scope.
-            thread::spawn(move || -> io::Result<()> {
-                player.play(player_id, turn_state, PLAYERS_NUMBER)?;
-                return Ok(());
-            });
-
-        // Adds the thread to the vector.
-        threads.push(thread);
+        supervisor.spawn(player, player_id, Arc::clone(&turn_state), PLAYERS_NUMBER);
     }
 
-    for thread in threads {
-        if let Err(e) = thread.join() {
-            log_error!("A thread has panicked: {:?}", e);
-
-            /*===========================================
-                TEST MINI SERVER PART
-            ===========================================*/
-
-            // let encoded_radar: String = register_player(
-            //     &mut stream.lock().unwrap(),
-            //     &registration_token,
-            //     &player_name,
-            // )?;
-            // print!("Received radar view: {}", encoded_radar);
-            // let radar_view_1: RadarView = RadarView::new(encoded_radar, CardinalDirection::North);
-
-            // log_debug!("Cardinal direction: {:?}", radar_view_1.cardinal_direction);
-            // print_string_matrix("Radar view 1", &radar_view_1.grid);
-            // let mut map: Map = Map::new(&radar_view_1.grid, radar_view_1.cardinal_direction);
-
-            // log_debug!(
-            //     "Cardinal direction of the map: {:?}",
-            //     map.current_cardinal_direction
-            // );
-            // print_string_matrix("Updated map", &map.grid.as_ref());
-
-            // let mut i: i32 = 0;
-            // for _ in 0..8 {
-            //     match map.next_move_tremaux() {
-            //         Some((relative_direction, chosen_cardinal_direction)) => {
-            //             i += 1;
-            //             log_info!("ITERATION: {}", i);
-            //             log_info!("Next move to send: {:?}", relative_direction);
-
-            //             let action: GameMessage = GameMessage::Action(Action::MoveTo(relative_direction));
-            //             let mut action_sent = false;
-            //             while !action_sent {
-            //                 {
-            //                     let mut stream_lock: std::sync::MutexGuard<'_, TcpStream> =
-            //                         stream.lock().unwrap();
-            //                     match action.send(&mut stream_lock) {
-            //                         Ok(_) => {
-            //                             log_info!("Action sent.");
-            //                             action_sent = true;
-            //                             break;
-            //                         }
-            //                         Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
-            //                             log_warning!("Broken pipe error, attempting to reconnect...");
-            //                             *stream_lock = connect_to_server(server_address)?;
-            //                         }
-            //                         Err(e) => return Err(e),
-            //                     }
-            //                 }
-            //                 thread::sleep(Duration::from_millis(10));
-            //             }
-            //             if !action_sent {
-            //                 return Err(io::Error::new(
-            //                     io::ErrorKind::BrokenPipe,
-            //                     "Failed to send action after multiple attempts",
-            //                 ));
-            //             }
-
-            //             let response: GameMessage;
-            //             {
-            //                 let mut stream_lock: std::sync::MutexGuard<'_, TcpStream> =
-            //                     stream.lock().unwrap();
-            //                 response = GameMessage::receive(&mut stream_lock)?;
-            //                 log_info!("Response received.");
-            //             }
-            //             match response {
-            //                 GameMessage::RadarView(new_radar_data) => {
-            //                     let new_radar_view: RadarView =
-            //                         RadarView::new(new_radar_data, chosen_cardinal_direction);
-            //                     let radar_view_log = format!("Radar view {}", i + 1);
-            //                     print_string_matrix(&radar_view_log, &new_radar_view.grid);
-            //                     map.merge_radar_view(&new_radar_view.grid, chosen_cardinal_direction);
-            //                     print_string_matrix("Updated map", &map.grid);
-            //                 }
-            //                 GameMessage::ActionError(err) => {
-            //                     log_warning!("Action error received: {:?}", err);
-            //                 }
-            //                 _ => {
-            //                     log_warning!("Unexpected message received.");
-            //                 }
-            //             }
-            //             thread::sleep(Duration::from_millis(10));
-            //         }
-            //         None => {
-            //             log_info!("No more possible moves. Stopping exploration.\n");
-            //             break;
-            //         }
+    let outcomes: Vec<PlayerOutcome> = supervisor.join();
+    let mut players_metrics: Vec<PlayerMetrics> = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes {
+        if let Some(failure) = &outcome.failure {
+            log_error!(
+                "{} has left the game after {} moves and {} restarts on {}",
+                outcome.metrics.name,
+                outcome.metrics.moves,
+                outcome.metrics.restarts,
+                failure
+            );
         }
+        players_metrics.push(outcome.metrics);
+    }
+
+    let report: TeamReport = TeamReport::new(&team.name, players_metrics);
+    println!("{}", report);
+    if let Some(path) = report_path {
+        report.write_json(path)?;
+        log_info!("The report has been written to {}", path);
+    }
+    if !report.has_finished {
+        log_warning!("The team has not finished: some players have not found the exit");
     }
 
-    return Ok(());
+    return Ok(report.has_finished);
 }
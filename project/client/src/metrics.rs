@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+use serde::Serialize;
+use shared::types::hint::Hint;
+
+/// What a player did during a game.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlayerMetrics {
+    pub name: String,
+    /// The moves sent to the server, refused ones included.
+    pub moves: u32,
+    pub wall_collisions: u32,
+    /// The accepted moves into a cell the player had already entered.
+    pub revisits: u32,
+    pub challenges_received: u32,
+    pub challenges_solved: u32,
+    pub challenges_rejected: u32,
+    /// The hints received, by type.
+    pub hints: BTreeMap<String, u32>,
+    pub turns: u32,
+    /// The total time spent playing the turns, waiting for the other players excluded.
+    pub turn_time_ms: u128,
+    pub max_turn_time_ms: u128,
+    pub cells_discovered: usize,
+    /// Set when the player reached the exit.
+    pub found_exit: bool,
+    /// The sessions resumed after a lost connection.
+    pub reconnections: u32,
+    /// The times the supervisor started the player again after a failure.
+    pub restarts: u32,
+    /// Set when the player failed for good before the end of the game.
+    pub is_disconnected: bool,
+}
+
+impl PlayerMetrics {
+    pub fn new(name: &str) -> PlayerMetrics {
+        return PlayerMetrics {
+            name: String::from(name),
+            ..PlayerMetrics::default()
+        };
+    }
+
+    pub fn record_hint(&mut self, hint: &Hint) {
+        let hint_type: &str = match hint {
+            Hint::RelativeCompass { .. } => "RelativeCompass",
+            Hint::GridSize { .. } => "GridSize",
+            Hint::Secret(_) => "Secret",
+            Hint::SOSHelper => "SOSHelper",
+        };
+        *self.hints.entry(String::from(hint_type)).or_insert(0) += 1;
+    }
+
+    /// Records an accepted move.
+    ///
+    /// # Arguments
+    ///
+    /// * `visits` - How many times the player has entered the cell, this move included.
+    pub fn record_accepted_move(&mut self, visits: u32) {
+        if 1 < visits {
+            self.revisits += 1;
+        }
+    }
+
+    pub fn record_turn(&mut self, duration: Duration) {
+        let duration_ms: u128 = duration.as_millis();
+        self.turns += 1;
+        self.turn_time_ms += duration_ms;
+        self.max_turn_time_ms = self.max_turn_time_ms.max(duration_ms);
+    }
+
+    pub fn average_turn_time_ms(&self) -> f64 {
+        if self.turns == 0 {
+            return 0.0;
+        }
+        return self.turn_time_ms as f64 / self.turns as f64;
+    }
+}
+
+/// The metrics of every player of a team and the score of the team.
+#[derive(Debug, Serialize)]
+pub struct TeamReport {
+    pub team: String,
+    pub players: Vec<PlayerMetrics>,
+    pub total_moves: u32,
+    /// Set when every player has found the exit.
+    pub has_finished: bool,
+    /// The score of the README: the moves of the whole team divided by its participants. The lower the better.
+    pub score: f64,
+}
+
+impl TeamReport {
+    pub fn new(team: &str, players: Vec<PlayerMetrics>) -> TeamReport {
+        let total_moves: u32 = players.iter().map(|player| player.moves).sum();
+        let has_finished: bool =
+            !players.is_empty() && players.iter().all(|player| player.found_exit);
+        let score: f64 = if players.is_empty() {
+            0.0
+        } else {
+            total_moves as f64 / players.len() as f64
+        };
+
+        return TeamReport {
+            team: String::from(team),
+            players,
+            total_moves,
+            has_finished,
+            score,
+        };
+    }
+
+    /// Writes the report as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write_json(&self, path: &str) -> io::Result<()> {
+        let json: String = serde_json::to_string_pretty(self)?;
+        return fs::write(path, json);
+    }
+}
+
+impl fmt::Display for TeamReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "===== {} =====", self.team)?;
+        for player in &self.players {
+            let hints: Vec<String> = player
+                .hints
+                .iter()
+                .map(|(hint_type, count)| format!("{}={}", hint_type, count))
+                .collect();
+            writeln!(f, "{}", player.name)?;
+            writeln!(
+                f,
+                "  moves: {}, wall collisions: {}, revisits: {}, cells discovered: {}",
+                player.moves, player.wall_collisions, player.revisits, player.cells_discovered
+            )?;
+            writeln!(
+                f,
+                "  challenges: {} received, {} solved, {} rejected",
+                player.challenges_received, player.challenges_solved, player.challenges_rejected
+            )?;
+            writeln!(f, "  hints: {}", hints.join(", "))?;
+            writeln!(
+                f,
+                "  exit found: {}, reconnections: {}, restarts: {}{}",
+                player.found_exit,
+                player.reconnections,
+                player.restarts,
+                if player.is_disconnected {
+                    ", disconnected"
+                } else {
+                    ""
+                }
+            )?;
+            writeln!(
+                f,
+                "  turns: {}, average {:.1} ms, max {} ms",
+                player.turns,
+                player.average_turn_time_ms(),
+                player.max_turn_time_ms
+            )?;
+        }
+        writeln!(
+            f,
+            "Exit found by {}/{} players",
+            self.players
+                .iter()
+                .filter(|player: &&PlayerMetrics| player.found_exit)
+                .count(),
+            self.players.len()
+        )?;
+        return write!(
+            f,
+            "Total moves: {}, score (moves / participants): {:.2}",
+            self.total_moves, self.score
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_metrics() {
+        let mut metrics: PlayerMetrics = PlayerMetrics::new("Player 1");
+        metrics.record_hint(&Hint::Secret(3));
+        metrics.record_hint(&Hint::Secret(4));
+        metrics.record_hint(&Hint::GridSize {
+            columns: 2,
+            rows: 2,
+        });
+        metrics.record_accepted_move(1);
+        metrics.record_accepted_move(2);
+        metrics.record_turn(Duration::from_millis(10));
+        metrics.record_turn(Duration::from_millis(30));
+
+        assert_eq!(metrics.hints["Secret"], 2);
+        assert_eq!(metrics.hints["GridSize"], 1);
+        assert_eq!(metrics.revisits, 1);
+        assert_eq!(metrics.turns, 2);
+        assert_eq!(metrics.max_turn_time_ms, 30);
+        assert_eq!(metrics.average_turn_time_ms(), 20.0);
+    }
+
+    #[test]
+    fn test_team_report() {
+        let mut first: PlayerMetrics = PlayerMetrics::new("Player 1");
+        first.moves = 10;
+        let mut second: PlayerMetrics = PlayerMetrics::new("Player 2");
+        second.moves = 5;
+
+        let report: TeamReport = TeamReport::new("Team 1", vec![first, second]);
+        assert_eq!(report.total_moves, 15);
+        assert_eq!(report.score, 7.5);
+        assert!(!report.has_finished);
+        assert!(report
+            .to_string()
+            .ends_with("score (moves / participants): 7.50"));
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["players"][1]["moves"], 5);
+    }
+}
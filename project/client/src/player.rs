@@ -1,34 +1,83 @@
 use std::collections::HashMap;
 use std::io;
-use std::net::TcpStream;
-use std::sync::{Arc, Condvar, Mutex};
+use std::net::{Shutdown, TcpStream};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use grid::export::export_map;
 use grid::map::Map;
 use grid::radar::RadarView;
+use grid::renderer::TeamView;
+use grid::snapshot::save_map;
+use grid::strategy::Strategy;
 use shared::types::action::Action;
 use shared::types::cardinal_direction::CardinalDirection;
 use shared::types::challenge::Challenge;
 use shared::types::error::ActionError;
 use shared::types::hint::Hint;
 use shared::types::message::GameMessage;
+use shared::utils::SessionStart;
+
+use crate::connection::{is_transient, Reconnection};
+use crate::coordinator::Coordinator;
+use crate::metrics::PlayerMetrics;
+use crate::supervisor::lock;
 
 pub struct TurnState {
     pub current: usize,
     pub game_over: bool,
     pub players_finished: usize,
+    /// The players which left the game, having found the exit or failed for good, skipped by the turns.
+    pub left: Vec<usize>,
+}
+
+impl TurnState {
+    /// Gives the turn to the next player still in the game.
+    pub fn next_turn(&mut self, total_players: usize) {
+        for _ in 0..total_players {
+            self.current = (self.current + 1) % total_players;
+            if !self.left.contains(&self.current) {
+                return;
+            }
+        }
+    }
+
+    /// Takes a player out of the turns, the game ending when no player is left.
+    pub fn leave(&mut self, player_id: usize, total_players: usize) {
+        if !self.left.contains(&player_id) {
+            self.left.push(player_id);
+        }
+        if total_players <= self.left.len() {
+            self.game_over = true;
+        } else if self.current == player_id {
+            self.next_turn(total_players);
+        }
+    }
 }
 
 pub struct Player {
     pub name: String,
     pub stream: TcpStream,
     pub map: Map,
+    /// How the player chooses its moves on its map.
+    pub strategy: Strategy,
+    /// Splits the exploration between the players of the team, if they coordinate.
+    pub coordinator: Option<Arc<Mutex<Coordinator>>>,
+    /// Resumes the session when the connection is lost, if set.
+    pub reconnection: Option<Reconnection>,
     pub cardinal_direction: CardinalDirection,
-    pub move_count: usize,
+    pub metrics: PlayerMetrics,
+    /// Draws the maps of the team in the terminal after each turn when set, shared by the players.
+    pub team_view: Option<Arc<Mutex<TeamView>>>,
+    /// The directory where the map is exported as a PNG at the end of the game, if any.
+    pub export_directory: Option<String>,
+    /// The directory where the map is saved at the end of the game, to be reloaded later, if any.
+    pub save_directory: Option<String>,
+    /// The challenge the server has sent to this player and which is not solved yet. The server keeps one
+    /// challenge per player: only the secrets are shared by the team.
     pub pending_challenge: Option<Challenge>,
     pub secrets: Arc<Mutex<HashMap<String, u64>>>,
-    pub global_challenge: Arc<Mutex<Option<Challenge>>>,
 }
 
 impl Player {
@@ -37,26 +86,29 @@ impl Player {
         stream: TcpStream,
         encoded_radar: String,
         secrets: Arc<Mutex<HashMap<String, u64>>>,
-        global_challenge: Arc<Mutex<Option<Challenge>>>,
     ) -> Self {
         let initial_radar: RadarView = RadarView::new(encoded_radar, CardinalDirection::North);
         let map: Map = Map::new(&initial_radar.grid, initial_radar.cardinal_direction);
 
         Self {
+            metrics: PlayerMetrics::new(&name),
+            team_view: None,
+            export_directory: None,
+            save_directory: None,
             name,
             stream,
             map,
+            strategy: Strategy::Tremaux,
+            coordinator: None,
+            reconnection: None,
             cardinal_direction: initial_radar.cardinal_direction,
-            move_count: 0,
             pending_challenge: None,
             secrets,
-            global_challenge,
         }
     }
 
     pub fn calculate_secret_sum(&self, modulo: u64) -> u64 {
-        let shared_secrets: std::sync::MutexGuard<'_, HashMap<String, u64>> =
-            self.secrets.lock().unwrap();
+        let shared_secrets: MutexGuard<'_, HashMap<String, u64>> = lock(&self.secrets);
         let sum128: u128 = shared_secrets
             .values()
             .fold(0u128, |accumulator: u128, &secret| {
@@ -65,9 +117,8 @@ impl Player {
         (sum128 % (modulo as u128)) as u64
     }
 
-    pub fn solve_global_challenge(&mut self, total_players: usize) -> io::Result<()> {
-        let maybe_challenge = self.global_challenge.lock().unwrap().clone();
-        match maybe_challenge {
+    pub fn solve_challenge(&mut self) -> io::Result<()> {
+        match self.pending_challenge {
             Some(Challenge::SecretSumModulo(modulo)) => {
                 let answer = self.calculate_secret_sum(modulo);
                 log_info!(
@@ -82,38 +133,59 @@ impl Player {
                 });
                 solve_msg.send(&mut self.stream)?;
 
-                let response = GameMessage::receive(&mut self.stream)?;
+                let response = self.receive_response()?;
                 log_info!(
                     "{}: Received response after solving challenge: {:?}",
                     self.name,
                     response
                 );
 
-                if let GameMessage::ActionError(ActionError::SolveChallengeFirst) = response {
-                    log_warning!(
-                        "{}: Challenge response rejected => re-calculate with updated secrets",
-                        self.name
-                    );
-                    self.solve_global_challenge(total_players)?;
-                } else {
-                    log_info!(
-                        "{}: Challenge solved successfully => clearing global challenge",
-                        self.name
-                    );
-                    let mut global_challenge: std::sync::MutexGuard<'_, Option<Challenge>> =
-                        self.global_challenge.lock().unwrap();
-                    *global_challenge = None;
+                match response {
+                    GameMessage::RadarView(new_radar_data) => {
+                        log_info!(
+                            "{}: Challenge solved successfully => clearing the challenge",
+                            self.name
+                        );
+                        // The view paused by the challenge is the one of the last move, already accepted.
+                        let move_direction: CardinalDirection = self.map.current_cardinal_direction;
+                        let new_radar: RadarView = RadarView::new(new_radar_data, move_direction);
+                        self.cardinal_direction = move_direction;
+                        self.map.commit_move(&new_radar.grid);
+                        self.metrics.challenges_solved += 1;
+                        self.pending_challenge = None;
+                    }
+                    GameMessage::ActionError(ActionError::InvalidChallengeSolution) => {
+                        self.metrics.challenges_rejected += 1;
+                        log_warning!(
+                            "{}: Challenge response rejected => re-calculate with updated secrets on next turn",
+                            self.name
+                        );
+                    }
+                    GameMessage::ActionError(ActionError::NoRunningChallenge) => {
+                        log_warning!(
+                            "{}: The server has no running challenge => clearing the challenge",
+                            self.name
+                        );
+                        self.pending_challenge = None;
+                    }
+                    other => {
+                        log_warning!(
+                            "{}: Unexpected response to the challenge: {:?}",
+                            self.name,
+                            other
+                        );
+                    }
                 }
             }
             Some(_) => {
                 log_warning!(
-                    "{}: A global challenge is set but is not SecretSumModulo",
+                    "{}: The pending challenge is not SecretSumModulo",
                     self.name
                 );
             }
             None => {
                 log_warning!(
-                    "{}: solve_global_challenge() called but no global challenge is set",
+                    "{}: solve_challenge() called but no challenge is pending",
                     self.name
                 );
             }
@@ -121,101 +193,215 @@ impl Player {
         Ok(())
     }
 
-    pub fn play(
-        mut self,
+    /// Receives the response to an action.
+    /// Hints may be sent before the response: they are processed and the next message is read.
+    pub fn receive_response(&mut self) -> io::Result<GameMessage> {
+        loop {
+            match GameMessage::receive(&mut self.stream)? {
+                GameMessage::Hint(hint) => self.process_hint(hint),
+                message => return Ok(message),
+            }
+        }
+    }
+
+    /// Counts a hint and keeps what it tells: a secret is shared with the team, a compass is recorded on
+    /// the map.
+    fn process_hint(&mut self, hint: Hint) {
+        self.metrics.record_hint(&hint);
+        match hint {
+            Hint::Secret(value) => {
+                log_info!("{} has received a secret from a hint: {}", self.name, value);
+                let mut shared_secrets: MutexGuard<'_, HashMap<String, u64>> = lock(&self.secrets);
+                shared_secrets.insert(self.name.clone(), value);
+            }
+            Hint::RelativeCompass { angle } => {
+                log_info!("{} has received a compass: {}", self.name, angle);
+                self.map.record_compass(angle);
+            }
+            hint => {
+                log_info!("{} has received a hint: {:?}", self.name, hint);
+            }
+        }
+    }
+
+    /// Plays the turns of the player until the game is over for it or the connection fails for good.
+    /// A lost connection is resumed when possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection cannot be resumed or the map cannot be drawn.
+    pub fn play_session(
+        &mut self,
         player_id: usize,
-        turn_state: Arc<(Mutex<TurnState>, Condvar)>,
+        turn_state: &Arc<(Mutex<TurnState>, Condvar)>,
         total_players: usize,
     ) -> io::Result<()> {
-        let has_finished: bool = false;
         loop {
-            let (lock, cvar) = &*turn_state;
-
-            // Safely unwraps the MutexGuard.
-            let mut state: std::sync::MutexGuard<'_, TurnState> = match lock.lock() {
-                Ok(state) => state,
-                Err(poisoned) => {
-                    log_warning!(
-                        "{}: Mutex is poisoned. The data you are using may be corrupted",
-                        self.name
-                    );
-                    poisoned.into_inner()
+            match self.play_turns(player_id, Arc::clone(turn_state), total_players) {
+                Err(e) if is_transient(&e) && self.reconnection.is_some() => {
+                    log_warning!("{} has lost its connection: {}", self.name, e);
+                    self.reconnect(false)?;
                 }
-            };
+                result => return result,
+            }
+        }
+    }
+
+    /// Starts the player again with its map after a failure, on a new connection since the old one may be
+    /// in the middle of an exchange.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session cannot be resumed, see `Reconnection::resume`.
+    pub fn restart(&mut self) -> io::Result<()> {
+        // The server only resumes the session once it has seen the old connection closed.
+        let _ = self.stream.shutdown(Shutdown::Both);
+        return self.reconnect(true);
+    }
+
+    /// Takes the player out of the game: a failed player leaves the turns and its sector to the others.
+    /// The map is saved and exported as asked.
+    ///
+    /// # Returns
+    ///
+    /// The metrics of the player.
+    pub fn leave(
+        mut self,
+        player_id: usize,
+        turn_state: &Arc<(Mutex<TurnState>, Condvar)>,
+        total_players: usize,
+        has_failed: bool,
+    ) -> PlayerMetrics {
+        if has_failed {
+            self.metrics.is_disconnected = true;
+            if let Some(coordinator) = &self.coordinator {
+                lock(coordinator).disconnect(player_id);
+            }
+            let (turn_lock, cvar) = &**turn_state;
+            lock(turn_lock).leave(player_id, total_players);
+            cvar.notify_all();
+        }
+        self.metrics.cells_discovered = self.map.discovered_cells();
+        if let Some(directory) = &self.save_directory {
+            let path: String = map_file_path(directory, &self.name, "json");
+            match save_map(&self.map, &path) {
+                Ok(()) => log_info!("{} has saved its map to {}", self.name, path),
+                Err(e) => log_error!("{} could not save its map: {}", self.name, e),
+            }
+        }
+        if let Some(directory) = &self.export_directory {
+            let path: String = map_file_path(directory, &self.name, "png");
+            match export_map(&self.map, &path) {
+                Ok(()) => log_info!("{} has exported its map to {}", self.name, path),
+                Err(e) => log_error!("{} could not export its map: {}", self.name, e),
+            }
+        }
+        return self.metrics;
+    }
 
+    fn play_turns(
+        &mut self,
+        player_id: usize,
+        turn_state: Arc<(Mutex<TurnState>, Condvar)>,
+        total_players: usize,
+    ) -> io::Result<()> {
+        loop {
+            let (turn_lock, cvar) = &*turn_state;
+            let mut state: MutexGuard<'_, TurnState> = lock(turn_lock);
             while state.current != player_id && !state.game_over {
-                state = cvar.wait(state).unwrap();
+                state = cvar
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
             }
             if state.game_over {
                 break;
             }
             drop(state);
 
-            if has_finished {
-                log_info!(
-                    "{} has already finished and is waiting for others.",
-                    self.name
-                );
+            let turn_start: Instant = Instant::now();
+            if self.pending_challenge.is_some() {
+                log_warning!("{} has a pending challenge => try to solve it", self.name);
+                self.solve_challenge()?;
             } else {
-                let gc_opt: Option<Challenge> = self.global_challenge.lock().unwrap().clone();
-                if gc_opt.is_some() {
-                    log_warning!("{} sees a global challenge => try to solve it", self.name);
-                    self.solve_global_challenge(total_players)?;
-                } else {
-                    match self.map.next_move_tremaux() {
-                        Some((relative_direction, chosen_cardinal_direction)) => {
-                            self.move_count += 1;
-                            let action: GameMessage =
-                                GameMessage::Action(Action::MoveTo(relative_direction));
-                            action.send(&mut self.stream)?;
-                            log_info!("{} has sent a move: {}", self.name, self.move_count);
-
-                            let response: GameMessage = GameMessage::receive(&mut self.stream)?;
-                            log_info!("{} has received a response: {:?}", self.name, response);
-
-                            match response {
-                                GameMessage::Challenge(Challenge::SecretSumModulo(m)) => {
-                                    log_info!(
-                                        "{}: Received a global SecretSumModulo: {}",
-                                        self.name,
-                                        m
-                                    );
-                                    let mut global_challenge: std::sync::MutexGuard<
-                                        '_,
-                                        Option<Challenge>,
-                                    > = self.global_challenge.lock().unwrap();
-                                    *global_challenge = Some(Challenge::SecretSumModulo(m));
-                                    drop(global_challenge);
-
-                                    self.solve_global_challenge(total_players)?;
-                                }
-                                GameMessage::RadarView(new_radar_data) => {
-                                    let new_radar: RadarView =
-                                        RadarView::new(new_radar_data, chosen_cardinal_direction);
-                                    self.cardinal_direction = chosen_cardinal_direction;
-                                    self.map.merge_radar_view(
-                                        &new_radar.grid,
-                                        chosen_cardinal_direction,
-                                    );
-                                }
-                                GameMessage::ActionError(err) => match err {
+                if let Some(coordinator) = &self.coordinator {
+                    lock(coordinator).coordinate(player_id, &mut self.map);
+                }
+                match self.strategy.next_move(&mut self.map) {
+                    Some((relative_direction, chosen_cardinal_direction)) => {
+                        self.metrics.moves += 1;
+                        let action: GameMessage =
+                            GameMessage::Action(Action::MoveTo(relative_direction));
+                        action.send(&mut self.stream)?;
+                        log_info!("{} has sent a move: {}", self.name, self.metrics.moves);
+
+                        let response: GameMessage = self.receive_response()?;
+                        log_info!("{} has received a response: {:?}", self.name, response);
+
+                        match response {
+                            GameMessage::Challenge(Challenge::SecretSumModulo(m)) => {
+                                log_info!("{}: Received a SecretSumModulo: {}", self.name, m);
+                                self.pending_challenge = Some(Challenge::SecretSumModulo(m));
+                                self.metrics.challenges_received += 1;
+                                self.set_available(player_id, false);
+
+                                // The server has moved the player, the view comes with the solution.
+                                self.map.accept_move();
+                                self.metrics
+                                    .record_accepted_move(self.map.visits_at_player());
+                                self.cardinal_direction = chosen_cardinal_direction;
+                                self.solve_challenge()?;
+                            }
+                            GameMessage::RadarView(new_radar_data) => {
+                                let new_radar: RadarView =
+                                    RadarView::new(new_radar_data, chosen_cardinal_direction);
+                                self.cardinal_direction = chosen_cardinal_direction;
+                                self.map.commit_move(&new_radar.grid);
+                                self.set_available(player_id, true);
+                                self.metrics
+                                    .record_accepted_move(self.map.visits_at_player());
+                            }
+                            GameMessage::ActionError(ActionError::KilledByMonster) => {
+                                log_warning!(
+                                    "{} was defeated by a monster and goes back to its spawn",
+                                    self.name
+                                );
+                                self.map.respawn();
+                                self.cardinal_direction = CardinalDirection::North;
+                            }
+                            GameMessage::ActionError(err) => {
+                                // The move is refused: the map stays where the player really is.
+                                self.map.rollback_move(&err);
+                                match err {
                                     ActionError::SolveChallengeFirst => {
                                         log_warning!(
                                             "{}: The server requires to solve a challenge first",
                                             self.name
                                         );
-                                        let mut global_challenge: std::sync::MutexGuard<
-                                            '_,
-                                            Option<Challenge>,
-                                        > = self.global_challenge.lock().unwrap();
-                                        if global_challenge.is_none() {
-                                            log_warning!("{} has called try_solve_challenge() but no global challenge is set", self.name);
+                                        if self.pending_challenge.is_none() {
+                                            log_warning!(
+                                                "{} has called try_solve_challenge() \
+                                                 but no challenge is pending",
+                                                self.name
+                                            );
                                         } else {
-                                            drop(global_challenge);
-                                            self.solve_global_challenge(total_players)?;
+                                            self.solve_challenge()?;
                                         }
                                     }
                                     ActionError::InvalidChallengeSolution => {}
+                                    ActionError::CannotPassThroughOpponent => {
+                                        log_warning!(
+                                            "{}: An opponent blocks the way => planning around it",
+                                            self.name
+                                        );
+                                        self.set_available(player_id, false);
+                                    }
+                                    ActionError::CannotPassThroughWall => {
+                                        self.metrics.wall_collisions += 1;
+                                        log_warning!(
+                                            "{}: A wall blocks the way => it is now on the map",
+                                            self.name
+                                        );
+                                    }
                                     _ => {
                                         log_warning!(
                                             "{} has performed a bad action: {:?}",
@@ -223,42 +409,47 @@ impl Player {
                                             err
                                         );
                                     }
-                                },
-                                GameMessage::Hint(Hint::Secret(value)) => {
-                                    log_info!(
-                                        "{} has received a secret from a hint: {}",
-                                        self.name,
-                                        value
-                                    );
-                                    let mut shared_secrets: std::sync::MutexGuard<
-                                        '_,
-                                        HashMap<String, u64>,
-                                    > = self.secrets.lock().unwrap();
-                                    shared_secrets.insert(self.name.clone(), value);
-                                }
-                                _ => {
-                                    log_warning!(
-                                        "{} has received an unexpected message",
-                                        self.name
-                                    );
                                 }
                             }
+                            _ => {
+                                log_warning!("{} has received an unexpected message", self.name);
+                            }
+                        }
 
-                            let (lock, cvar) = &*turn_state;
-                            let mut state: std::sync::MutexGuard<'_, TurnState> =
-                                lock.lock().unwrap();
-                            state.current = (state.current + 1) % total_players;
-                            cvar.notify_all();
+                        self.metrics.cells_discovered = self.map.discovered_cells();
+                        self.metrics.record_turn(turn_start.elapsed());
+                        if let Some(team_view) = &self.team_view {
+                            // A terminal which cannot be drawn must not stop the game.
+                            if let Err(e) = lock(team_view).update(player_id, &self.name, &self.map)
+                            {
+                                log_warning!("{} cannot draw the team view: {}", self.name, e);
+                            }
                         }
-                        None => {
-                            log_info!("{} has no more moves available, game over", self.name);
-                            let (lock, cvar) = &*turn_state;
-                            let mut state: std::sync::MutexGuard<'_, TurnState> =
-                                lock.lock().unwrap();
-                            state.game_over = true;
+
+                        let (turn_lock, cvar) = &*turn_state;
+                        let mut state: MutexGuard<'_, TurnState> = lock(turn_lock);
+                        if self.map.known_goal() == Some(self.map.player_position) {
+                            log_info!(
+                                "{} has found the exit after {} moves",
+                                self.name,
+                                self.metrics.moves
+                            );
+                            self.metrics.found_exit = true;
+                            state.players_finished += 1;
+                            state.leave(player_id, total_players);
                             cvar.notify_all();
                             break;
                         }
+                        state.next_turn(total_players);
+                        cvar.notify_all();
+                    }
+                    None => {
+                        log_info!("{} has no more moves available, game over", self.name);
+                        let (turn_lock, cvar) = &*turn_state;
+                        let mut state: MutexGuard<'_, TurnState> = lock(turn_lock);
+                        state.game_over = true;
+                        cvar.notify_all();
+                        break;
                     }
                 }
             }
@@ -267,4 +458,158 @@ impl Player {
 
         return Ok(());
     }
+
+    /// Opens the connection again and resumes the session where the server left the player.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_restart` - Whether the player closed its old connection itself, see `Reconnection::resume`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session cannot be resumed, see `Reconnection::resume`.
+    fn reconnect(&mut self, is_restart: bool) -> io::Result<()> {
+        let reconnection: Reconnection = match &self.reconnection {
+            Some(reconnection) => reconnection.clone(),
+            None => return Err(io::Error::from(io::ErrorKind::NotConnected)),
+        };
+        let (stream, session_start, hints) = reconnection.resume(&self.name, is_restart)?;
+        self.stream = stream;
+        match session_start {
+            SessionStart::RadarView(encoded_radar) => {
+                self.cardinal_direction = self.map.resume(&encoded_radar);
+            }
+            // The server has paused the views: the player solves the challenge on its next turn.
+            SessionStart::Challenge(challenge) => {
+                log_warning!("{} resumes its session in a challenge", self.name);
+                self.pending_challenge = Some(challenge);
+            }
+        }
+        // The hints come with the view: a compass is relative to the heading the view has just given.
+        for hint in hints {
+            self.process_hint(hint);
+        }
+        self.metrics.reconnections += 1;
+        log_info!("{} has resumed its session", self.name);
+        return Ok(());
+    }
+
+    /// Tells the coordinator, if any, whether the player can explore: not while an opponent blocks it or
+    /// a challenge holds it.
+    fn set_available(&self, player_id: usize, is_available: bool) {
+        if let Some(coordinator) = &self.coordinator {
+            lock(coordinator).set_available(player_id, is_available);
+        }
+    }
+}
+
+/// Returns the path of the file holding the map of a player in a directory.
+pub fn map_file_path(directory: &str, player_name: &str, extension: &str) -> String {
+    return format!(
+        "{}/{}.{}",
+        directory,
+        player_name.replace(' ', "_"),
+        extension
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::types::message::SubscribePlayerResult;
+    use std::net::TcpListener;
+
+    /// Creates a player connected to a test server, with the stream of the server.
+    fn player_with_server(
+        name: &str,
+        secrets: Arc<Mutex<HashMap<String, u64>>>,
+    ) -> (Player, TcpStream) {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream: TcpStream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let player: Player = Player::new(
+            String::from(name),
+            stream,
+            String::from("ieysGjGO8papd/a"),
+            secrets,
+        );
+        return (player, server);
+    }
+
+    #[test]
+    fn test_challenges_are_kept_per_player() {
+        let secrets: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (mut player_1, mut server_1) = player_with_server("Player 1", Arc::clone(&secrets));
+        let (mut player_2, _server_2) = player_with_server("Player 2", Arc::clone(&secrets));
+        player_1.pending_challenge = Some(Challenge::SecretSumModulo(7));
+        player_2.pending_challenge = Some(Challenge::SecretSumModulo(5));
+
+        // The server has no challenge for the first player: its teammate keeps its own.
+        GameMessage::ActionError(ActionError::NoRunningChallenge)
+            .send(&mut server_1)
+            .unwrap();
+        player_1.solve_challenge().unwrap();
+        assert!(matches!(
+            GameMessage::receive(&mut server_1).unwrap(),
+            GameMessage::Action(Action::SolveChallenge { .. })
+        ));
+        assert!(player_1.pending_challenge.is_none());
+        assert!(matches!(
+            player_2.pending_challenge,
+            Some(Challenge::SecretSumModulo(5))
+        ));
+    }
+
+    #[test]
+    fn test_resume_keeps_the_hints() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = listener.local_addr().unwrap().to_string();
+        let server: thread::JoinHandle<()> = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            GameMessage::receive(&mut stream).unwrap();
+            GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)
+                .send(&mut stream)
+                .unwrap();
+            GameMessage::Hint(Hint::Secret(9))
+                .send(&mut stream)
+                .unwrap();
+            GameMessage::RadarView(String::from("ieysGjGO8papd/a"))
+                .send(&mut stream)
+                .unwrap();
+        });
+
+        let secrets: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (mut player, _old_server) = player_with_server("Player 1", Arc::clone(&secrets));
+        let mut reconnection: Reconnection = Reconnection::new(&address, "token");
+        reconnection.initial_backoff = Duration::from_millis(1);
+        player.reconnection = Some(reconnection);
+
+        player.reconnect(false).unwrap();
+        server.join().unwrap();
+        assert_eq!(player.metrics.reconnections, 1);
+        assert_eq!(player.metrics.hints.get("Secret"), Some(&1));
+        assert_eq!(lock(&secrets).get("Player 1"), Some(&9));
+    }
+
+    #[test]
+    fn test_turns_skip_left_players() {
+        let mut state: TurnState = TurnState {
+            current: 0,
+            game_over: false,
+            players_finished: 0,
+            left: Vec::new(),
+        };
+        state.leave(1, 3);
+        state.next_turn(3);
+        assert_eq!(state.current, 2);
+        state.next_turn(3);
+        assert_eq!(state.current, 0);
+
+        // The player holding the turn passes it on.
+        state.leave(0, 3);
+        assert_eq!(state.current, 2);
+        assert!(!state.game_over);
+        state.leave(2, 3);
+        assert!(state.game_over);
+    }
 }
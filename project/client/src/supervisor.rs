@@ -0,0 +1,271 @@
+use std::any::Any;
+use std::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+use shared::types::log::log::Log;
+
+use crate::metrics::PlayerMetrics;
+use crate::player::{Player, TurnState};
+
+/// Why a player stopped before the end of the game.
+#[derive(Debug)]
+pub enum Failure {
+    /// The connection failed and could not be resumed, or the map could not be drawn.
+    Error(io::Error),
+    /// The thread of the player panicked, with the panic message.
+    Panic(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Failure::Error(e) => write!(f, "error: {}", e),
+            Failure::Panic(message) => write!(f, "panic: {}", message),
+        };
+    }
+}
+
+/// How a player ended the game.
+#[derive(Debug)]
+pub struct PlayerOutcome {
+    pub metrics: PlayerMetrics,
+    /// The last failure of the player, if it could not play until the end.
+    pub failure: Option<Failure>,
+}
+
+/// Runs each player in its own thread and collects how each of them ended.
+///
+/// A player which fails, whether on an error or a panic, is restarted with its map up to `max_restarts`
+/// times: it opens a new connection and resumes its session. Once it gives up, the team plays on without
+/// it and its failure is reported with its metrics.
+pub struct Supervisor {
+    pub max_restarts: u32,
+    workers: Vec<(String, thread::JoinHandle<PlayerOutcome>)>,
+}
+
+impl Supervisor {
+    pub fn new(max_restarts: u32) -> Supervisor {
+        return Supervisor {
+            max_restarts,
+            workers: Vec::new(),
+        };
+    }
+
+    /// Starts the thread of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The player, moved to its thread.
+    /// * `player_id` - The turn of the player.
+    /// * `turn_state` - The turns shared by the players.
+    /// * `total_players` - The number of players taking turns.
+    pub fn spawn(
+        &mut self,
+        player: Player,
+        player_id: usize,
+        turn_state: Arc<(Mutex<TurnState>, Condvar)>,
+        total_players: usize,
+    ) {
+        let name: String = player.name.clone();
+        let max_restarts: u32 = self.max_restarts;
+        let worker: thread::JoinHandle<PlayerOutcome> = thread::spawn(move || -> PlayerOutcome {
+            // Every line logged by this thread tells which player wrote it.
+            Log::set_context("player", &player.name);
+            return supervise(
+                player,
+                player_id,
+                &turn_state,
+                total_players,
+                max_restarts,
+                |player: &mut Player| -> io::Result<()> {
+                    return player.play_session(player_id, &turn_state, total_players);
+                },
+            );
+        });
+        self.workers.push((name, worker));
+    }
+
+    /// Waits for every player to end, in the order they were started.
+    pub fn join(self) -> Vec<PlayerOutcome> {
+        let mut outcomes: Vec<PlayerOutcome> = Vec::with_capacity(self.workers.len());
+        for (name, worker) in self.workers {
+            // The panics of the players are caught by `supervise`, this one comes from the supervision.
+            let outcome: PlayerOutcome =
+                worker
+                    .join()
+                    .unwrap_or_else(|payload: Box<dyn Any + Send>| PlayerOutcome {
+                        metrics: PlayerMetrics::new(&name),
+                        failure: Some(Failure::Panic(panic_message(payload.as_ref()))),
+                    });
+            outcomes.push(outcome);
+        }
+        return outcomes;
+    }
+}
+
+/// Plays a player until the end of the game, restarting it on failure while allowed.
+///
+/// # Arguments
+///
+/// * `play_session` - Plays the turns of the player, see `Player::play_session`.
+fn supervise(
+    mut player: Player,
+    player_id: usize,
+    turn_state: &Arc<(Mutex<TurnState>, Condvar)>,
+    total_players: usize,
+    max_restarts: u32,
+    mut play_session: impl FnMut(&mut Player) -> io::Result<()>,
+) -> PlayerOutcome {
+    let failure: Option<Failure> = loop {
+        let failure: Failure =
+            match panic::catch_unwind(AssertUnwindSafe(|| play_session(&mut player))) {
+                Ok(Ok(())) => break None,
+                Ok(Err(e)) => Failure::Error(e),
+                Err(payload) => Failure::Panic(panic_message(payload.as_ref())),
+            };
+        log_error!("{} has failed on {}", player.name, failure);
+        if max_restarts <= player.metrics.restarts {
+            break Some(failure);
+        }
+        player.metrics.restarts += 1;
+        log_warning!(
+            "{} restarts with its map ({}/{})",
+            player.name,
+            player.metrics.restarts,
+            max_restarts
+        );
+        if let Err(e) = player.restart() {
+            break Some(Failure::Error(e));
+        }
+    };
+    let metrics: PlayerMetrics =
+        player.leave(player_id, turn_state, total_players, failure.is_some());
+    return PlayerOutcome { metrics, failure };
+}
+
+/// Returns the message a thread panicked with.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return String::from(*message);
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    return String::from("unknown panic");
+}
+
+/// Locks a mutex shared by the players, even when another player panicked while holding it: the data is
+/// then used as the panic left it.
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    return mutex.lock().unwrap_or_else(|poisoned| {
+        log_warning!("A mutex is poisoned by a panic, its data may be inconsistent");
+        poisoned.into_inner()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Reconnection;
+    use crate::metrics::TeamReport;
+    use shared::types::message::{GameMessage, SubscribePlayerResult};
+    use std::collections::HashMap;
+    use std::net::{TcpListener, TcpStream};
+    use std::time::Duration;
+
+    #[test]
+    fn test_panicking_player_is_restarted_then_leaves() {
+        // The server resumes the session of the player on each restart.
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = listener.local_addr().unwrap().to_string();
+        let server: thread::JoinHandle<Vec<TcpStream>> = thread::spawn(move || {
+            let mut streams: Vec<TcpStream> = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                GameMessage::receive(&mut stream).unwrap();
+                GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok)
+                    .send(&mut stream)
+                    .unwrap();
+                GameMessage::RadarView(String::from("ieysGjGO8papd/a"))
+                    .send(&mut stream)
+                    .unwrap();
+                streams.push(stream);
+            }
+            return streams;
+        });
+
+        let first_listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream: TcpStream = TcpStream::connect(first_listener.local_addr().unwrap()).unwrap();
+        let mut player: Player = Player::new(
+            String::from("Player 1"),
+            stream,
+            String::from("ieysGjGO8papd/a"),
+            Arc::new(Mutex::new(HashMap::new())),
+        );
+        let mut reconnection: Reconnection = Reconnection::new(&address, "token");
+        reconnection.initial_backoff = Duration::from_millis(1);
+        player.reconnection = Some(reconnection);
+        let turn_state: Arc<(Mutex<TurnState>, Condvar)> = Arc::new((
+            Mutex::new(TurnState {
+                current: 0,
+                game_over: false,
+                players_finished: 0,
+                left: Vec::new(),
+            }),
+            Condvar::new(),
+        ));
+
+        let mut sessions: u32 = 0;
+        let outcome: PlayerOutcome = supervise(
+            player,
+            0,
+            &turn_state,
+            2,
+            2,
+            |_: &mut Player| -> io::Result<()> {
+                sessions += 1;
+                panic!("the player panicked");
+            },
+        );
+        server.join().unwrap();
+
+        // The first session then one per restart, the last failure being final.
+        assert_eq!(sessions, 3);
+        assert_eq!(outcome.metrics.restarts, 2);
+        assert_eq!(outcome.metrics.reconnections, 2);
+        assert!(outcome.metrics.is_disconnected);
+        assert!(matches!(
+            &outcome.failure,
+            Some(Failure::Panic(message)) if message == "the player panicked"
+        ));
+
+        // The player has left the turns to its teammate.
+        let state: MutexGuard<'_, TurnState> = lock(&turn_state.0);
+        assert_eq!(state.left, vec![0]);
+        assert_eq!(state.current, 1);
+        assert!(!state.game_over);
+
+        // The team has not finished: the worker exits with 1.
+        assert!(!TeamReport::new("team", vec![outcome.metrics]).has_finished);
+    }
+
+    #[test]
+    fn test_poisoned_lock() {
+        let mutex: Arc<Mutex<u32>> = Arc::new(Mutex::new(1));
+        let poisoner: Arc<Mutex<u32>> = Arc::clone(&mutex);
+        let payload: Box<dyn Any + Send> = thread::spawn(move || {
+            let _guard: MutexGuard<'_, u32> = poisoner.lock().unwrap();
+            panic!("the player panicked");
+        })
+        .join()
+        .unwrap_err();
+
+        assert_eq!(panic_message(payload.as_ref()), "the player panicked");
+        assert!(mutex.is_poisoned());
+        *lock(&mutex) += 1;
+        assert_eq!(*lock(&mutex), 2);
+    }
+}
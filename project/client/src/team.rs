@@ -12,7 +12,6 @@ pub struct Team {
     pub registration_token: String,
     pub players: Vec<Player>,
     pub secrets: Arc<Mutex<HashMap<String, u64>>>,
-    pub global_challenge: Arc<Mutex<Option<shared::types::challenge::Challenge>>>,
 }
 
 impl Team {
@@ -22,9 +21,8 @@ impl Team {
         members_number: usize,
     ) -> io::Result<Self> {
         let mut stream: TcpStream = connect_to_server(server_address)?;
-        let registration_token: String = register_team(&mut stream, &team_name)?;
+        let registration_token: String = register_team(&mut stream, team_name)?;
         let mut secrets: HashMap<String, u64> = HashMap::new();
-        let mut global_challenge: Option<shared::types::challenge::Challenge> = None;
         for i in 0..members_number {
             let player_name: String = format!("Player {}", i + 1);
             secrets.insert(player_name, 0);
@@ -34,7 +32,6 @@ impl Team {
             registration_token,
             players: Vec::new(),
             secrets: Arc::new(Mutex::new(secrets)),
-            global_challenge: Arc::new(Mutex::new(global_challenge)),
         })
     }
 
@@ -49,7 +46,6 @@ impl Team {
             stream,
             encoded_radar,
             self.secrets.clone(),
-            self.global_challenge.clone(),
         );
 
         self.players.push(player);
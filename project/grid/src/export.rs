@@ -0,0 +1,330 @@
+use image::{ImageResult, Rgb, RgbImage};
+
+use crate::map::Map;
+use crate::maze::{Cell, Maze};
+use shared::types::cardinal_direction::CardinalDirection;
+
+/// The size of a cell in pixels, its walls included.
+const CELL_SIZE: u32 = 16;
+/// The thickness of the walls and of the path in pixels.
+const LINE_WIDTH: u32 = 2;
+/// The margin between the border of a cell and its marker in pixels.
+const MARKER_MARGIN: u32 = 4;
+
+const WALL_COLOR: Rgb<u8> = Rgb([0, 0, 0]);
+const OPEN_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+const UNKNOWN_COLOR: Rgb<u8> = Rgb([170, 170, 170]);
+const PATH_COLOR: Rgb<u8> = Rgb([30, 90, 220]);
+const PLAYER_COLOR: Rgb<u8> = Rgb([10, 30, 120]);
+const GOAL_COLOR: Rgb<u8> = Rgb([20, 170, 40]);
+const HINT_COLOR: Rgb<u8> = Rgb([240, 190, 0]);
+const ALLY_COLOR: Rgb<u8> = Rgb([80, 160, 255]);
+const OPPONENT_COLOR: Rgb<u8> = Rgb([220, 30, 30]);
+const MONSTER_COLOR: Rgb<u8> = Rgb([150, 40, 180]);
+/// The colors of the least and of the most visited cells of the heatmap.
+const HEATMAP_COLORS: (Rgb<u8>, Rgb<u8>) = (Rgb([255, 237, 160]), Rgb([230, 80, 40]));
+
+/// Exports a map to a PNG file: its walls, the unknown areas in gray, the visits as a heatmap,
+/// the path taken by the player, its current position and the markers of the radar.
+///
+/// # Arguments
+///
+/// * `map` - The map to export.
+/// * `path` - The path of the PNG file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn export_map(map: &Map, path: &str) -> ImageResult<()> {
+    return map_to_image(map).save(path);
+}
+
+/// Exports a whole maze to a PNG file: its walls, its exit, its hints and a path taken in it.
+///
+/// # Arguments
+///
+/// * `maze` - The maze to export.
+/// * `path_taken` - The cells entered by a player, in order. It may be empty.
+/// * `path` - The path of the PNG file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn export_maze(maze: &Maze, path_taken: &[Cell], path: &str) -> ImageResult<()> {
+    return maze_to_image(maze, path_taken).save(path);
+}
+
+/// Draws a map, each cell of its grid being `CELL_SIZE` pixels wide.
+pub fn map_to_image(map: &Map) -> RgbImage {
+    let ((top, left), (bottom, right)) = map.grid.bounds().unwrap_or(((0, 0), (0, 0)));
+    let rows: u32 = ((bottom - top) / 2) as u32;
+    let columns: u32 = ((right - left) / 2) as u32;
+    let mut image: RgbImage = new_image(columns, rows);
+    let max_visits: u32 = map
+        .visits
+        .positions()
+        .map(|position| map.visits.value(position))
+        .max()
+        .unwrap_or(0);
+    // The image coordinates of the cell at a position of the grid.
+    let to_image = |(row, column): (isize, isize)| -> (u32, u32) {
+        return (((column - left) / 2) as u32, ((row - top) / 2) as u32);
+    };
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let (grid_row, grid_column) =
+                (top + 2 * row as isize + 1, left + 2 * column as isize + 1);
+            let color: Rgb<u8> = match map.cell(grid_row, grid_column) {
+                "#" => UNKNOWN_COLOR,
+                _ => heatmap_color(map.visits.value((grid_row, grid_column)), max_visits),
+            };
+            fill_cell(&mut image, (column, row), color);
+        }
+    }
+
+    let path_taken: Vec<(u32, u32)> = map
+        .path
+        .iter()
+        .map(|&position| to_image(position))
+        .collect();
+    draw_path(&mut image, &path_taken);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let (grid_row, grid_column) =
+                (top + 2 * row as isize + 1, left + 2 * column as isize + 1);
+            let content: &str = map
+                .entity(grid_row, grid_column)
+                .unwrap_or(map.cell(grid_row, grid_column));
+            let marker: Option<Rgb<u8>> = match content {
+                "G" => Some(GOAL_COLOR),
+                "H" => Some(HINT_COLOR),
+                "A" => Some(ALLY_COLOR),
+                "E" => Some(OPPONENT_COLOR),
+                "M" => Some(MONSTER_COLOR),
+                _ => None,
+            };
+            if let Some(color) = marker {
+                draw_marker(&mut image, (column, row), color);
+            }
+
+            if map.cell(grid_row - 1, grid_column) == "-" {
+                draw_wall(&mut image, (column, row), CardinalDirection::North);
+            }
+            if map.cell(grid_row + 1, grid_column) == "-" {
+                draw_wall(&mut image, (column, row), CardinalDirection::South);
+            }
+            if map.cell(grid_row, grid_column - 1) == "|" {
+                draw_wall(&mut image, (column, row), CardinalDirection::West);
+            }
+            if map.cell(grid_row, grid_column + 1) == "|" {
+                draw_wall(&mut image, (column, row), CardinalDirection::East);
+            }
+        }
+    }
+
+    draw_marker(&mut image, to_image(map.player_position), PLAYER_COLOR);
+
+    return image;
+}
+
+/// Draws a whole maze, each cell being `CELL_SIZE` pixels wide.
+pub fn maze_to_image(maze: &Maze, path_taken: &[Cell]) -> RgbImage {
+    let mut image: RgbImage = new_image(maze.columns as u32, maze.rows as u32);
+
+    let path_taken: Vec<(u32, u32)> = path_taken
+        .iter()
+        .map(|&(column, row)| (column as u32, row as u32))
+        .collect();
+    draw_path(&mut image, &path_taken);
+
+    for &(column, row) in &maze.hints {
+        draw_marker(&mut image, (column as u32, row as u32), HINT_COLOR);
+    }
+    draw_marker(
+        &mut image,
+        (maze.goal.0 as u32, maze.goal.1 as u32),
+        GOAL_COLOR,
+    );
+
+    for row in 0..maze.rows {
+        for column in 0..maze.columns {
+            for direction in [
+                CardinalDirection::North,
+                CardinalDirection::East,
+                CardinalDirection::South,
+                CardinalDirection::West,
+            ] {
+                if maze.has_wall((column, row), direction) {
+                    draw_wall(&mut image, (column as u32, row as u32), direction);
+                }
+            }
+        }
+    }
+
+    return image;
+}
+
+fn new_image(columns: u32, rows: u32) -> RgbImage {
+    return RgbImage::from_pixel(
+        columns * CELL_SIZE + LINE_WIDTH,
+        rows * CELL_SIZE + LINE_WIDTH,
+        OPEN_COLOR,
+    );
+}
+
+/// Returns the color of a cell according to its visits, relatively to the most visited cell.
+fn heatmap_color(visits: u32, max_visits: u32) -> Rgb<u8> {
+    if visits == 0 {
+        return OPEN_COLOR;
+    }
+
+    let ratio: f32 = visits as f32 / max_visits as f32;
+    let (Rgb(low), Rgb(high)) = HEATMAP_COLORS;
+    let mut color: [u8; 3] = [0; 3];
+    for channel in 0..3 {
+        color[channel] =
+            (low[channel] as f32 + (high[channel] as f32 - low[channel] as f32) * ratio) as u8;
+    }
+    return Rgb(color);
+}
+
+fn fill_rectangle(
+    image: &mut RgbImage,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+    color: Rgb<u8>,
+) {
+    for pixel_x in x..(x + width).min(image.width()) {
+        for pixel_y in y..(y + height).min(image.height()) {
+            image.put_pixel(pixel_x, pixel_y, color);
+        }
+    }
+}
+
+fn fill_cell(image: &mut RgbImage, (column, row): (u32, u32), color: Rgb<u8>) {
+    fill_rectangle(
+        image,
+        (column * CELL_SIZE, row * CELL_SIZE),
+        (CELL_SIZE + LINE_WIDTH, CELL_SIZE + LINE_WIDTH),
+        color,
+    );
+}
+
+fn draw_marker(image: &mut RgbImage, (column, row): (u32, u32), color: Rgb<u8>) {
+    let size: u32 = CELL_SIZE + LINE_WIDTH - 2 * MARKER_MARGIN;
+    fill_rectangle(
+        image,
+        (
+            column * CELL_SIZE + MARKER_MARGIN,
+            row * CELL_SIZE + MARKER_MARGIN,
+        ),
+        (size, size),
+        color,
+    );
+}
+
+fn draw_wall(image: &mut RgbImage, (column, row): (u32, u32), direction: CardinalDirection) {
+    let (x, y): (u32, u32) = (column * CELL_SIZE, row * CELL_SIZE);
+    let (origin, size) = match direction {
+        CardinalDirection::North => ((x, y), (CELL_SIZE + LINE_WIDTH, LINE_WIDTH)),
+        CardinalDirection::South => ((x, y + CELL_SIZE), (CELL_SIZE + LINE_WIDTH, LINE_WIDTH)),
+        CardinalDirection::West => ((x, y), (LINE_WIDTH, CELL_SIZE + LINE_WIDTH)),
+        CardinalDirection::East => ((x + CELL_SIZE, y), (LINE_WIDTH, CELL_SIZE + LINE_WIDTH)),
+    };
+    fill_rectangle(image, origin, size, WALL_COLOR);
+}
+
+/// Links the centers of the consecutive cells of a path. The jumps between cells which are not
+/// next to each other, such as a respawn, are not drawn.
+fn draw_path(image: &mut RgbImage, path: &[(u32, u32)]) {
+    let center = |(column, row): (u32, u32)| -> (u32, u32) {
+        return (
+            column * CELL_SIZE + (CELL_SIZE + LINE_WIDTH) / 2 - LINE_WIDTH / 2,
+            row * CELL_SIZE + (CELL_SIZE + LINE_WIDTH) / 2 - LINE_WIDTH / 2,
+        );
+    };
+
+    for step in path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        if from.0.abs_diff(to.0) + from.1.abs_diff(to.1) != 1 {
+            continue;
+        }
+        let ((from_x, from_y), (to_x, to_y)) = (center(from), center(to));
+        fill_rectangle(
+            image,
+            (from_x.min(to_x), from_y.min(to_y)),
+            (
+                from_x.abs_diff(to_x) + LINE_WIDTH,
+                from_y.abs_diff(to_y) + LINE_WIDTH,
+            ),
+            PATH_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::utils::string_to_strings;
+
+    /// Returns the color at the center of a cell.
+    fn center_color(image: &RgbImage, column: u32, row: u32) -> Rgb<u8> {
+        return *image.get_pixel(
+            column * CELL_SIZE + CELL_SIZE / 2 + 1,
+            row * CELL_SIZE + CELL_SIZE / 2 + 1,
+        );
+    }
+
+    #[test]
+    fn test_map_to_image() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|G    |"),
+            string_to_strings("• •-• •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•##"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (3, 1);
+        map.visits.set((3, 3), 2);
+        map.path = vec![(3, 3), (3, 1)];
+
+        let image: RgbImage = map_to_image(&map);
+        assert_eq!(image.dimensions(), (3 * CELL_SIZE + 2, 2 * CELL_SIZE + 2));
+        assert_eq!(center_color(&image, 0, 0), GOAL_COLOR);
+        assert_eq!(center_color(&image, 0, 1), PLAYER_COLOR);
+        assert_eq!(center_color(&image, 2, 0), OPEN_COLOR);
+        // The path crosses the border between the two cells.
+        assert_eq!(
+            *image.get_pixel(CELL_SIZE - 1, CELL_SIZE + CELL_SIZE / 2),
+            PATH_COLOR
+        );
+        // The wall between the goal and the cell below it.
+        assert_eq!(*image.get_pixel(CELL_SIZE + 4, CELL_SIZE), WALL_COLOR);
+        assert_eq!(
+            *image.get_pixel(CELL_SIZE + CELL_SIZE / 2, CELL_SIZE + 4),
+            HEATMAP_COLORS.1
+        );
+    }
+
+    #[test]
+    fn test_export_maze() {
+        let mut maze: Maze = Maze::open(3, 2);
+        maze.hints.push((0, 1));
+        let image: RgbImage = maze_to_image(&maze, &[(0, 0), (1, 0)]);
+        assert_eq!(center_color(&image, 2, 1), GOAL_COLOR);
+        assert_eq!(center_color(&image, 0, 1), HINT_COLOR);
+        assert_eq!(*image.get_pixel(0, 0), WALL_COLOR);
+
+        let path: String = std::env::temp_dir()
+            .join("grid_test_export_maze.png")
+            .to_string_lossy()
+            .into_owned();
+        export_maze(&maze, &[], &path).unwrap();
+        let bytes: Vec<u8> = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[1..4], b"PNG");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use shared::types::cardinal_direction::CardinalDirection;
+
+use crate::maze::{Cell, Maze};
+
+const DIRECTIONS: [CardinalDirection; 4] = [
+    CardinalDirection::North,
+    CardinalDirection::East,
+    CardinalDirection::South,
+    CardinalDirection::West,
+];
+
+/// The algorithms carving a perfect maze: every cell is reachable by exactly one path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generator {
+    RecursiveBacktracking,
+    Prims,
+    Ellers,
+    GrowingTree,
+}
+
+impl Generator {
+    /// Returns the name of the generator on the command line.
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Generator::RecursiveBacktracking => "recursive-backtracking",
+            Generator::Prims => "prims",
+            Generator::Ellers => "ellers",
+            Generator::GrowingTree => "growing-tree",
+        };
+    }
+}
+
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return match value {
+            "recursive-backtracking" => Ok(Generator::RecursiveBacktracking),
+            "prims" => Ok(Generator::Prims),
+            "ellers" => Ok(Generator::Ellers),
+            "growing-tree" => Ok(Generator::GrowingTree),
+            _ => Err(format!("Unknown generator: {}", value)),
+        };
+    }
+}
+
+/// How to generate a maze.
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    pub generator: Generator,
+    pub seed: u64,
+    /// The probability, between 0 and 1, to open each dead end, adding loops to the perfect maze.
+    pub braid: f64,
+}
+
+/// Generates a maze. The same options always give the same maze.
+///
+/// # Arguments
+///
+/// * `columns` - The width of the maze, at least 1.
+/// * `rows` - The height of the maze, at least 1.
+/// * `options` - The algorithm, its seed and the braiding.
+pub fn generate(columns: usize, rows: usize, options: &GeneratorOptions) -> Maze {
+    let mut rng: StdRng = StdRng::seed_from_u64(options.seed);
+    let mut maze: Maze = match options.generator {
+        Generator::RecursiveBacktracking => recursive_backtracking(columns, rows, &mut rng),
+        Generator::Prims => prims(columns, rows, &mut rng),
+        Generator::Ellers => ellers(columns, rows, &mut rng),
+        Generator::GrowingTree => growing_tree(columns, rows, &mut rng),
+    };
+    if 0.0 < options.braid {
+        braid(&mut maze, options.braid, &mut rng);
+    }
+    return maze;
+}
+
+/// Walks randomly from a cell, carving as long as it finds unvisited cells, then backtracks to the last
+/// cell which has some. Gives long corridors with few dead ends.
+pub fn recursive_backtracking(columns: usize, rows: usize, rng: &mut StdRng) -> Maze {
+    return grow(columns, rows, rng, 1.0);
+}
+
+/// Grows the maze from a list of active cells, picking the newest one or a random one with the same
+/// probability. It mixes the long corridors of the backtracking with the short branches of Prim's.
+pub fn growing_tree(columns: usize, rows: usize, rng: &mut StdRng) -> Maze {
+    return grow(columns, rows, rng, 0.5);
+}
+
+/// Grows the maze from a random cell by opening random walls between a visited and an unvisited cell.
+/// Gives many short dead ends.
+pub fn prims(columns: usize, rows: usize, rng: &mut StdRng) -> Maze {
+    let mut maze: Maze = Maze::new(columns, rows);
+    let mut visited: Vec<Vec<bool>> = vec![vec![false; columns]; rows];
+    let start: Cell = (rng.gen_range(0..columns), rng.gen_range(0..rows));
+    visited[start.1][start.0] = true;
+
+    let mut frontier: Vec<(Cell, CardinalDirection)> = unvisited_neighbours(&maze, start, &visited)
+        .into_iter()
+        .map(|(direction, _)| (start, direction))
+        .collect();
+    while !frontier.is_empty() {
+        let (cell, direction) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        let next: Cell = match maze.neighbour(cell, direction) {
+            Some(next) if !visited[next.1][next.0] => next,
+            _ => continue,
+        };
+
+        maze.set_wall(cell, direction, false);
+        visited[next.1][next.0] = true;
+        for (next_direction, _) in unvisited_neighbours(&maze, next, &visited) {
+            frontier.push((next, next_direction));
+        }
+    }
+
+    return maze;
+}
+
+/// Carves the maze row by row, only remembering which cells of the current row are connected.
+/// Each connected set of a row goes down at least once so that no cell is isolated, and the last row
+/// joins all the remaining sets.
+pub fn ellers(columns: usize, rows: usize, rng: &mut StdRng) -> Maze {
+    let mut maze: Maze = Maze::new(columns, rows);
+    // The set of each cell of the current row. `0` is a cell which has not been reached from above.
+    let mut sets: Vec<usize> = vec![0; columns];
+    let mut next_set: usize = 1;
+
+    for row in 0..rows {
+        let is_last_row: bool = row + 1 == rows;
+        for set in sets.iter_mut().filter(|set: &&mut usize| **set == 0) {
+            *set = next_set;
+            next_set += 1;
+        }
+
+        for column in 0..columns.saturating_sub(1) {
+            if sets[column] != sets[column + 1] && (is_last_row || rng.gen_bool(0.5)) {
+                maze.set_wall((column, row), CardinalDirection::East, false);
+                let (kept, merged) = (sets[column], sets[column + 1]);
+                for set in sets.iter_mut().filter(|set: &&mut usize| **set == merged) {
+                    *set = kept;
+                }
+            }
+        }
+
+        if is_last_row {
+            break;
+        }
+        let mut next_sets: Vec<usize> = vec![0; columns];
+        let mut row_sets: Vec<usize> = sets.clone();
+        row_sets.sort_unstable();
+        row_sets.dedup();
+        for set in row_sets {
+            let mut members: Vec<usize> = (0..columns).filter(|&c| sets[c] == set).collect();
+            members.shuffle(rng);
+            let going_down: usize = rng.gen_range(1..=members.len());
+            for &column in &members[..going_down] {
+                maze.set_wall((column, row), CardinalDirection::South, false);
+                next_sets[column] = set;
+            }
+        }
+        sets = next_sets;
+    }
+
+    return maze;
+}
+
+/// Opens dead ends to add loops to a maze. A dead end is preferably joined to another dead end.
+///
+/// # Arguments
+///
+/// * `maze` - The maze to braid.
+/// * `ratio` - The probability, between 0 and 1, to open each dead end.
+/// * `rng` - The random generator.
+pub fn braid(maze: &mut Maze, ratio: f64, rng: &mut StdRng) {
+    let mut cells: Vec<Cell> = (0..maze.rows)
+        .flat_map(|row: usize| (0..maze.columns).map(move |column: usize| (column, row)))
+        .collect();
+    cells.shuffle(rng);
+
+    for cell in cells {
+        if !is_dead_end(maze, cell) || !rng.gen_bool(ratio.min(1.0)) {
+            continue;
+        }
+
+        let walls: Vec<(CardinalDirection, Cell)> = DIRECTIONS
+            .iter()
+            .filter(|&&direction| maze.has_wall(cell, direction))
+            .filter_map(|&direction| {
+                maze.neighbour(cell, direction)
+                    .map(|next| (direction, next))
+            })
+            .collect();
+        let dead_ends: Vec<(CardinalDirection, Cell)> = walls
+            .iter()
+            .copied()
+            .filter(|&(_, next)| is_dead_end(maze, next))
+            .collect();
+        let candidates: &Vec<(CardinalDirection, Cell)> = if dead_ends.is_empty() {
+            &walls
+        } else {
+            &dead_ends
+        };
+        if let Some(&(direction, _)) = candidates.choose(rng) {
+            maze.set_wall(cell, direction, false);
+        }
+    }
+}
+
+/// Carves a maze from a list of active cells. The cell extended at each step is the newest one with the
+/// probability `newest_ratio`, otherwise a random one.
+fn grow(columns: usize, rows: usize, rng: &mut StdRng, newest_ratio: f64) -> Maze {
+    let mut maze: Maze = Maze::new(columns, rows);
+    let mut visited: Vec<Vec<bool>> = vec![vec![false; columns]; rows];
+    let start: Cell = (rng.gen_range(0..columns), rng.gen_range(0..rows));
+    visited[start.1][start.0] = true;
+    let mut active: Vec<Cell> = vec![start];
+
+    while !active.is_empty() {
+        let index: usize = if rng.gen_bool(newest_ratio) {
+            active.len() - 1
+        } else {
+            rng.gen_range(0..active.len())
+        };
+        let cell: Cell = active[index];
+
+        match unvisited_neighbours(&maze, cell, &visited).choose(rng) {
+            Some(&(direction, next)) => {
+                maze.set_wall(cell, direction, false);
+                visited[next.1][next.0] = true;
+                active.push(next);
+            }
+            None => {
+                active.remove(index);
+            }
+        }
+    }
+
+    return maze;
+}
+
+fn unvisited_neighbours(
+    maze: &Maze,
+    cell: Cell,
+    visited: &[Vec<bool>],
+) -> Vec<(CardinalDirection, Cell)> {
+    return DIRECTIONS
+        .iter()
+        .filter_map(|&direction| {
+            maze.neighbour(cell, direction)
+                .map(|next| (direction, next))
+        })
+        .filter(|&(_, (column, row))| !visited[row][column])
+        .collect();
+}
+
+fn is_dead_end(maze: &Maze, cell: Cell) -> bool {
+    return DIRECTIONS
+        .iter()
+        .filter(|&&direction| maze.has_wall(cell, direction))
+        .count()
+        == 3;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATORS: [Generator; 4] = [
+        Generator::RecursiveBacktracking,
+        Generator::Prims,
+        Generator::Ellers,
+        Generator::GrowingTree,
+    ];
+
+    fn options(generator: Generator, seed: u64, braid: f64) -> GeneratorOptions {
+        return GeneratorOptions {
+            generator,
+            seed,
+            braid,
+        };
+    }
+
+    /// Counts the passages between two cells and the cells reachable from the top left one.
+    fn passages_and_reachable_cells(maze: &Maze) -> (usize, usize) {
+        let passages: usize = (0..maze.rows)
+            .flat_map(|row: usize| (0..maze.columns).map(move |column: usize| (column, row)))
+            .map(|cell: Cell| maze.open_neighbours(cell).len())
+            .sum::<usize>()
+            / 2;
+
+        let mut visited: Vec<Vec<bool>> = vec![vec![false; maze.columns]; maze.rows];
+        let mut stack: Vec<Cell> = vec![(0, 0)];
+        visited[0][0] = true;
+        let mut reachable: usize = 0;
+        while let Some(cell) = stack.pop() {
+            reachable += 1;
+            for (column, row) in maze.open_neighbours(cell) {
+                if !visited[row][column] {
+                    visited[row][column] = true;
+                    stack.push((column, row));
+                }
+            }
+        }
+
+        return (passages, reachable);
+    }
+
+    #[test]
+    fn test_perfect_mazes() {
+        for generator in GENERATORS {
+            for (columns, rows) in [(1, 1), (1, 7), (12, 1), (10, 10), (17, 9)] {
+                let maze: Maze = generate(columns, rows, &options(generator, 7, 0.0));
+                assert_eq!(
+                    passages_and_reachable_cells(&maze),
+                    (columns * rows - 1, columns * rows),
+                    "{} is not perfect in {}x{}",
+                    generator.name(),
+                    columns,
+                    rows
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_generation() {
+        for generator in GENERATORS {
+            let first: Maze = generate(10, 10, &options(generator, 42, 0.0));
+            let second: Maze = generate(10, 10, &options(generator, 42, 0.0));
+            let other: Maze = generate(10, 10, &options(generator, 43, 0.0));
+            assert_eq!(first.horizontal_walls, second.horizontal_walls);
+            assert_eq!(first.vertical_walls, second.vertical_walls);
+            assert_ne!(first.vertical_walls, other.vertical_walls);
+        }
+    }
+
+    #[test]
+    fn test_braid() {
+        for generator in GENERATORS {
+            let maze: Maze = generate(10, 10, &options(generator, 3, 1.0));
+            let (passages, reachable) = passages_and_reachable_cells(&maze);
+            assert_eq!(reachable, 100);
+            assert!(99 < passages);
+            for row in 0..10 {
+                for column in 0..10 {
+                    assert!(!is_dead_end(&maze, (column, row)));
+                }
+            }
+        }
+        assert_eq!("ellers".parse::<Generator>(), Ok(Generator::Ellers));
+        assert!("kruskal".parse::<Generator>().is_err());
+    }
+}
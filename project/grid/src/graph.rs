@@ -0,0 +1,182 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use shared::types::cardinal_direction::CardinalDirection;
+
+use crate::map::{is_cell, Map};
+use crate::store::Position;
+
+/// A corridor of the junction graph, from one node to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub to: Position,
+    /// The number of moves along the corridor.
+    pub length: u32,
+    /// The first move of the corridor.
+    pub direction: CardinalDirection,
+}
+
+/// The known cells of a map with their corridors collapsed: the nodes are the junctions, the dead ends
+/// and the cells the planners aim at, the edges are the corridors between them weighted by their length.
+///
+/// Only the passages of the map are followed, see `Map::passages`: the sealed branches are left, never
+/// entered.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph {
+    edges: HashMap<Position, Vec<Edge>>,
+}
+
+impl JunctionGraph {
+    /// Builds the junction graph of the known cells of a map.
+    pub fn new(map: &Map) -> JunctionGraph {
+        let goal: Option<Position> = map.known_goal();
+        let hints: Vec<Position> = map.unvisited_hints();
+        let is_node = |position: Position, passages: usize| -> bool {
+            return passages != 2
+                || position == map.player_position
+                || Some(position) == goal
+                || hints.contains(&position)
+                || map.is_frontier(position);
+        };
+
+        let mut nodes: Vec<Position> = Vec::new();
+        for position in map.grid.positions() {
+            if is_cell(position)
+                && map.cell(position.0, position.1) != "#"
+                && is_node(position, map.passages(position).len())
+            {
+                nodes.push(position);
+            }
+        }
+        // The player may stand on a cell a monster was seen around.
+        if !nodes.contains(&map.player_position) {
+            nodes.push(map.player_position);
+        }
+
+        let mut edges: HashMap<Position, Vec<Edge>> = HashMap::new();
+        for &node in &nodes {
+            let mut node_edges: Vec<Edge> = Vec::new();
+            for (direction, first) in map.passages(node) {
+                let mut previous: Position = node;
+                let mut current: Position = first;
+                let mut length: u32 = 1;
+                let mut is_ring: bool = false;
+                loop {
+                    let passages: Vec<(CardinalDirection, Position)> = map.passages(current);
+                    if is_node(current, passages.len()) {
+                        break;
+                    }
+                    let next: Position = passages
+                        .iter()
+                        .map(|&(_, next): &(CardinalDirection, Position)| next)
+                        .find(|&next: &Position| next != previous)
+                        .unwrap_or(previous);
+                    previous = current;
+                    current = next;
+                    length += 1;
+                    // A ring of corridor cells the node only leads into, the way back being refused.
+                    if current == first {
+                        is_ring = true;
+                        break;
+                    }
+                }
+                if current != node && !is_ring {
+                    node_edges.push(Edge {
+                        to: current,
+                        length,
+                        direction,
+                    });
+                }
+            }
+            edges.insert(node, node_edges);
+        }
+        return JunctionGraph { edges };
+    }
+
+    pub fn is_node(&self, position: Position) -> bool {
+        return self.edges.contains_key(&position);
+    }
+
+    /// Returns the corridors leaving a node, empty if the position is not a node.
+    pub fn edges(&self, position: Position) -> &[Edge] {
+        return self.edges.get(&position).map_or(&[], Vec::as_slice);
+    }
+
+    /// Computes the shortest paths from a node to the other nodes.
+    ///
+    /// # Returns
+    ///
+    /// The distance in moves of every reachable node, with the first move of a shortest path towards it.
+    /// The start node is at distance 0, its direction meaningless.
+    pub fn distances(&self, from: Position) -> HashMap<Position, (u32, CardinalDirection)> {
+        let mut distances: HashMap<Position, (u32, CardinalDirection)> = HashMap::new();
+        if !self.is_node(from) {
+            return distances;
+        }
+        distances.insert(from, (0, CardinalDirection::North));
+
+        // The positions break the ties, for the paths not to depend on the hash map order.
+        let mut queue: BinaryHeap<Reverse<(u32, Position)>> = BinaryHeap::new();
+        queue.push(Reverse((0, from)));
+        while let Some(Reverse((distance, node))) = queue.pop() {
+            if distances[&node].0 < distance {
+                continue;
+            }
+            for edge in self.edges(node) {
+                let to_distance: u32 = distance + edge.length;
+                let direction: CardinalDirection = if node == from {
+                    edge.direction
+                } else {
+                    distances[&node].1
+                };
+                let is_shorter: bool = distances
+                    .get(&edge.to)
+                    .is_none_or(|&(known, _): &(u32, CardinalDirection)| to_distance < known);
+                if is_shorter {
+                    distances.insert(edge.to, (to_distance, direction));
+                    queue.push(Reverse((to_distance, edge.to)));
+                }
+            }
+        }
+        return distances;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::utils::string_to_strings;
+
+    #[test]
+    fn test_corridors_collapsed() {
+        // A corridor going east from the player then turning south and west, towards unknown cells.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•"),
+            string_to_strings("|       |"),
+            string_to_strings("•-•-•-• •"),
+            string_to_strings("#####   |"),
+            string_to_strings("•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (1, 1);
+        map.seal_dead_ends();
+
+        let graph: JunctionGraph = map.junction_graph();
+        assert!(graph.is_node((1, 1)));
+        assert!(!graph.is_node((1, 3)));
+        assert!(!graph.is_node((3, 7)));
+        assert!(graph.is_node((3, 5)));
+        assert_eq!(
+            graph.edges((1, 1)),
+            &[Edge {
+                to: (3, 5),
+                length: 5,
+                direction: CardinalDirection::East,
+            }]
+        );
+
+        let distances: HashMap<Position, (u32, CardinalDirection)> = graph.distances((1, 1));
+        assert_eq!(distances[&(3, 5)], (5, CardinalDirection::East));
+        assert_eq!(distances.len(), 2);
+    }
+}
@@ -1,5 +1,15 @@
+pub mod export;
+pub mod generator;
+pub mod graph;
 pub mod map;
+pub mod maze;
+pub mod plan;
 pub mod radar;
+pub mod renderer;
+pub mod simulator;
+pub mod snapshot;
+pub mod store;
+pub mod strategy;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
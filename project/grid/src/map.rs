@@ -1,89 +1,433 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use shared::log_debug;
+use rand::SeedableRng;
 use shared::types::action::RelativeDirection;
 use shared::types::cardinal_direction::CardinalDirection;
+use shared::types::error::ActionError;
+use shared::{log_debug, log_warning};
+
+use crate::graph::JunctionGraph;
+use crate::plan::Plan;
+use crate::radar::RadarView;
+use crate::store::{Position, SparseGrid};
+
+/// The directions of the moves, in the order the planners try them.
+const DIRECTIONS: [CardinalDirection; 4] = [
+    CardinalDirection::North,
+    CardinalDirection::East,
+    CardinalDirection::South,
+    CardinalDirection::West,
+];
+
+/// A candidate move: its cardinal direction, its relative direction, the cell offset and the wall offset.
+type RandomMove = (
+    CardinalDirection,
+    RelativeDirection,
+    (isize, isize),
+    (isize, isize),
+);
+
+/// The rank of a candidate move: whether its cell is sealed, whether an entity makes it dangerous, the marks
+/// of its passage, its cell priority then the blocked attempts of its cell. The lowest wins.
+type MoveRank = (bool, bool, u8, u8, u32);
+
+/// The number of teammates, the attacker included, needed next to a monster to defeat it, unless the
+/// server is started with another `--monster-group-size`.
+pub const MONSTER_GROUP_SIZE: u32 = 2;
+
+/// The number of radar views during which an entity is still expected where it was last seen.
+const ENTITY_MEMORY: u32 = 2;
+
+/// The number of moves a player accepts to spend on a detour to a hint with `HintPolicy::Opportunistic`.
+const HINT_DETOUR: u32 = 4;
+
+/// The moves the frontier strategy accepts to spend for each unknown cell its radar would reveal.
+const INFORMATION_WEIGHT: f32 = 0.5;
+
+/// The moves the frontier strategy accepts to spend to head straight to the exit given by the compass.
+pub const COMPASS_WEIGHT: f32 = 3.0;
+
+/// The moves the frontier strategy adds to the frontier cells outside the sector of the player.
+const SECTOR_PENALTY: f32 = 8.0;
+
+/// Whether the players walk to the hint cells they know, where the server sends them a hint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintPolicy {
+    /// The hint cells are cells like the others.
+    Ignore,
+    /// Goes to an unvisited hint cell when it is at most `HINT_DETOUR` moves away.
+    Opportunistic,
+    /// Goes to the nearest unvisited hint cell before exploring further.
+    Always,
+}
+
+impl HintPolicy {
+    /// Returns the name of the policy on the command line.
+    pub fn name(&self) -> &'static str {
+        return match self {
+            HintPolicy::Ignore => "ignore",
+            HintPolicy::Opportunistic => "opportunistic",
+            HintPolicy::Always => "always",
+        };
+    }
+}
+
+impl FromStr for HintPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return match value {
+            "ignore" => Ok(HintPolicy::Ignore),
+            "opportunistic" => Ok(HintPolicy::Opportunistic),
+            "always" => Ok(HintPolicy::Always),
+            _ => Err(format!("Unknown hint policy: {}", value)),
+        };
+    }
+}
 
+/// A moving entity seen on a cell: its marker, `A` for an ally, `E` for an opponent or `M` for a monster,
+/// and the radar view which showed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sighting {
+    pub marker: String,
+    pub turn: u32,
+}
+
+/// What a player knows of the maze. The positions are signed: they never change when the map grows,
+/// the cells having odd rows and columns, the corners even ones and the walls the others.
+#[derive(Clone)]
 pub struct Map {
     pub player_position: (isize, isize),
-    pub grid: Vec<Vec<String>>,
-    pub visits: Vec<Vec<u32>>,
+    /// The cell where the player entered the maze, where a monster sends it back.
+    pub spawn_position: (isize, isize),
+    /// What does not change during a game: the walls, the open cells, the exit and the hints.
+    /// `#` is unknown.
+    pub grid: SparseGrid<String>,
+    /// The entities last seen on each cell. They move, so they are only trusted for a few views.
+    pub sightings: SparseGrid<Option<Sighting>>,
+    /// The number of radar views merged since the start, which dates the sightings.
+    pub turn: u32,
+    /// The number of times a radar view disagreed with the walls or the cells already known.
+    pub contradictions: u32,
+    /// The cells of the explored dead ends, see `seal_dead_ends`. Nothing is left to find there.
+    pub sealed: SparseGrid<bool>,
+    pub visits: SparseGrid<u32>,
+    /// The number of times the player went through each passage, on the wall positions: 0, 1 or 2.
+    /// These are the marks of Trémaux's algorithm.
+    pub marks: SparseGrid<u8>,
+    /// The moves refused because an opponent stood on the cell. They are not counted as visits.
+    pub blocked_attempts: SparseGrid<u32>,
     pub current_cardinal_direction: CardinalDirection,
+    /// The move proposed by the planner and sent to the server, applied once the server accepts it.
+    pub pending_move: Option<CardinalDirection>,
+    /// The positions of the player since the start, the current one included.
+    pub path: Vec<(isize, isize)>,
+    /// The positions of the hint cells seen, in the order they were seen.
+    pub hints: Vec<(isize, isize)>,
+    pub hint_policy: HintPolicy,
+    /// The moves chosen by the wall follower, each one from its cell, those refused by the server excepted.
+    /// Choosing one again means that the follower goes around an island.
+    pub follower_moves: HashSet<(Position, CardinalDirection)>,
+    /// Set once the wall follower went around an island, see `next_move_wall_follower`.
+    pub is_follower_looping: bool,
+    /// The direction of the exit given by the last compass hint, in degrees clockwise from North.
+    pub goal_bearing: Option<f32>,
+    /// The moves the frontier strategy accepts to spend to head straight to the exit given by the compass.
+    pub compass_weight: f32,
+    /// The part of the maze to explore first, given by a team coordinator: the cells in this direction
+    /// from the origin of the sectors, see `sector_of`.
+    pub sector: Option<CardinalDirection>,
+    /// The cell the sectors start from, the same cell of the maze for the whole team, set by the
+    /// coordinator once it has aligned the maps. `None` is the spawn.
+    pub sector_origin: Option<Position>,
+    /// The number of teammates, the attacker included, needed next to a monster to defeat it. It must
+    /// match the `--monster-group-size` of the server.
+    pub monster_group_size: u32,
+    /// The moves planned towards a hint, the exit or a frontier cell, made over the next turns.
+    pub plan: Option<Plan>,
+    /// The random generator of `next_move_random`. The simulator seeds it so that its games can be
+    /// played again.
+    pub rng: StdRng,
 }
 
 impl Map {
-    pub fn new(
-        initial_grid: &Vec<Vec<String>>,
+    /// Creates a map from a first grid, whose entity markers become sightings.
+    /// The players take the hints on their way, see `HintPolicy::Opportunistic`.
+    pub fn new(initial_grid: &[Vec<String>], current_cardinal_direction: CardinalDirection) -> Map {
+        return Map::new_at(initial_grid, (0, 0), current_cardinal_direction);
+    }
+
+    /// Creates a map from a first grid whose top left position is `origin`, the player at its center.
+    pub fn new_at(
+        initial_grid: &[Vec<String>],
+        origin: Position,
         current_cardinal_direction: CardinalDirection,
     ) -> Map {
-        let grid: Vec<Vec<String>> = initial_grid.clone();
-        let view_size: usize = grid.len();
-        let center: (isize, isize) = if view_size > 0 && grid[0].len() > 0 {
-            (view_size as isize / 2, grid[0].len() as isize / 2)
+        let mut grid: SparseGrid<String> =
+            SparseGrid::from_rows(initial_grid, origin, |position| {
+                String::from(unknown_content(position))
+            });
+        let center: (isize, isize) = if !initial_grid.is_empty() && !initial_grid[0].is_empty() {
+            (
+                origin.0 + initial_grid.len() as isize / 2,
+                origin.1 + initial_grid[0].len() as isize / 2,
+            )
         } else {
-            (0, 0)
+            origin
         };
-        let visits: Vec<Vec<u32>> = vec![vec![0; grid[0].len()]; grid.len()];
-        Map {
+        let mut sightings: SparseGrid<Option<Sighting>> = SparseGrid::new(|_| None);
+        let mut hints: Vec<(isize, isize)> = Vec::new();
+        let positions: Vec<Position> = grid.positions().collect();
+        for position in positions {
+            let content: &mut String = grid.get_mut(position);
+            if content == "H" {
+                hints.push(position);
+            }
+            if is_entity(content) {
+                sightings.set(
+                    position,
+                    Some(Sighting {
+                        marker: std::mem::replace(content, String::from(" ")),
+                        turn: 0,
+                    }),
+                );
+            }
+        }
+        let mut map: Map = Map {
             player_position: center,
+            spawn_position: center,
             grid,
-            visits,
+            sightings,
+            turn: 0,
+            contradictions: 0,
+            sealed: SparseGrid::new(|_| false),
+            visits: SparseGrid::new(|_| 0),
+            marks: SparseGrid::new(|_| 0),
+            blocked_attempts: SparseGrid::new(|_| 0),
             current_cardinal_direction,
-        }
+            pending_move: None,
+            path: vec![center],
+            hints,
+            hint_policy: HintPolicy::Opportunistic,
+            follower_moves: HashSet::new(),
+            is_follower_looping: false,
+            goal_bearing: None,
+            compass_weight: COMPASS_WEIGHT,
+            sector: None,
+            sector_origin: None,
+            monster_group_size: MONSTER_GROUP_SIZE,
+            plan: None,
+            rng: StdRng::from_entropy(),
+        };
+        map.seal_dead_ends();
+        return map;
     }
 
+    /// Moves the player one cell towards `move_direction` then merges the radar view seen from there.
     pub fn merge_radar_view(
         &mut self,
-        new_view: &Vec<Vec<String>>,
+        new_view: &[Vec<String>],
         move_direction: CardinalDirection,
     ) {
-        self.current_cardinal_direction = move_direction;
-        self.expand_grid_if_needed();
-        self.merge_radar_view_to_map_grid(new_view);
+        self.pending_move = Some(move_direction);
+        self.commit_move(new_view);
     }
 
-    pub fn merge_radar_view_to_map_grid(&mut self, new_view: &Vec<Vec<String>>) {
-        let view_size: usize = new_view.len();
-        let half = view_size / 2;
+    /// Applies the pending move, accepted by the server, then merges the radar view seen after it.
+    /// Without a pending move, the view is merged at the current position.
+    pub fn commit_move(&mut self, new_view: &[Vec<String>]) {
+        self.accept_move();
+        self.merge_radar_view_to_map_grid(new_view);
+    }
 
-        let grid_rows: usize = self.grid.len();
-        let grid_cols: usize = self.grid[0].len();
-        let mut start_row: usize = if self.player_position.0 as usize >= half {
-            self.player_position.0 as usize - half
-        } else {
-            0
+    /// Applies the pending move when the server accepted it but sent no radar view yet,
+    /// which happens when a challenge replaces the view.
+    pub fn accept_move(&mut self) {
+        let move_direction: CardinalDirection = match self.pending_move.take() {
+            Some(move_direction) => move_direction,
+            None => return,
         };
-        let mut start_col: usize = if self.player_position.1 as usize >= half {
-            self.player_position.1 as usize - half
-        } else {
-            0
+
+        self.current_cardinal_direction = move_direction;
+        let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+            move_offsets(move_direction);
+        let marks: &mut u8 = self.marks.get_mut((
+            self.player_position.0 + wall_row_offset,
+            self.player_position.1 + wall_col_offset,
+        ));
+        *marks = (*marks + 1).min(2);
+        if let Some(plan) = &mut self.plan {
+            if plan.from == self.player_position && plan.next_move() == Some(move_direction) {
+                plan.advance();
+            } else {
+                self.plan = None;
+            }
+        }
+        self.player_position = (
+            self.player_position.0 + row_offset,
+            self.player_position.1 + col_offset,
+        );
+        *self.visits.get_mut(self.player_position) += 1;
+        self.path.push(self.player_position);
+    }
+
+    /// Cancels the pending move, refused by the server, and learns from the refusal so that the planner
+    /// does not retry it:
+    ///
+    /// * `CannotPassThroughWall` - The wall is written into the map.
+    /// * `CannotPassThroughOpponent` - The attempt is recorded instead of a visit and the cell is an obstacle
+    ///   until a radar view frees it.
+    pub fn rollback_move(&mut self, error: &ActionError) {
+        let move_direction: CardinalDirection = match self.pending_move.take() {
+            Some(move_direction) => move_direction,
+            None => return,
         };
-        if grid_rows >= view_size {
-            start_row = start_row.min(grid_rows - view_size);
+        self.plan = None;
+        // The player did not leave its cell: the wall follower may choose the move again.
+        self.follower_moves
+            .remove(&(self.player_position, move_direction));
+
+        let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+            move_offsets(move_direction);
+        let (row, column) = self.player_position;
+        match error {
+            ActionError::CannotPassThroughWall => {
+                self.grid.set(
+                    (row + wall_row_offset, column + wall_col_offset),
+                    String::from(match move_direction {
+                        CardinalDirection::North | CardinalDirection::South => "-",
+                        CardinalDirection::East | CardinalDirection::West => "|",
+                    }),
+                );
+            }
+            ActionError::CannotPassThroughOpponent => {
+                let target: Position = (row + row_offset, column + col_offset);
+                *self.blocked_attempts.get_mut(target) += 1;
+                self.sightings.set(
+                    target,
+                    Some(Sighting {
+                        marker: String::from("E"),
+                        turn: self.turn,
+                    }),
+                );
+            }
+            _ => {}
         }
-        if grid_cols >= view_size {
-            start_col = start_col.min(grid_cols - view_size);
+    }
+
+    /// Merges a radar view centered on the player into the map.
+    ///
+    /// The unknown parts of the view (`#`) never replace what is known. The entities of the view are
+    /// recorded as sightings of this view, and the visible cells without entity clear the older ones.
+    /// A view contradicting a known wall or a known cell is counted in `contradictions`, and the newest
+    /// view is trusted.
+    pub fn merge_radar_view_to_map_grid(&mut self, new_view: &[Vec<String>]) {
+        let view_size: usize = new_view.len();
+        if view_size == 0 || new_view[0].is_empty() {
+            return;
         }
+        let half: isize = (view_size / 2) as isize;
+        let top_left: Position = (self.player_position.0 - half, self.player_position.1 - half);
+        self.grid.cover(
+            top_left,
+            (
+                top_left.0 + view_size as isize - 1,
+                top_left.1 + new_view[0].len() as isize - 1,
+            ),
+        );
 
         log_debug!(
-            "Merge_radar_view_to_map_grid: grid {}x{}",
-            grid_rows,
-            grid_cols
-        );
-        for i in 0..view_size {
-            for j in 0..view_size {
-                // log_debug!(
-                //     "Merging cell view[{}][{}] into grid[{}][{}]",
-                //     i,
-                //     j,
-                //     start_row + i,
-                //     start_col + j
-                // );
-                if new_view[i][j] != "#" {
-                    self.grid[start_row + i][start_col + j] = new_view[i][j].clone();
+            "Merge_radar_view_to_map_grid: view centered on {:?}",
+            self.player_position
+        );
+        self.turn += 1;
+        let contradictions: u32 = self.contradictions;
+        let mut cells: Vec<Position> = Vec::new();
+        let mut is_plan_outdated: bool = false;
+        for (i, view_row) in new_view.iter().enumerate() {
+            for (j, view_cell) in view_row.iter().enumerate() {
+                let position: Position = (top_left.0 + i as isize, top_left.1 + j as isize);
+                if view_cell != "#" {
+                    is_plan_outdated |= self.merge_position(position, view_cell);
+                }
+                if is_cell(position) {
+                    cells.push(position);
                 }
             }
         }
+
+        if is_plan_outdated {
+            log_debug!("The radar view outdates the plan {:?}", self.plan);
+            self.plan = None;
+        }
+        // A wall which disappeared may reopen a sealed dead end.
+        if contradictions < self.contradictions {
+            self.seal_dead_ends();
+        } else {
+            self.seal_dead_ends_from(cells);
+        }
+    }
+
+    /// Merges what a radar view shows at a position of the grid.
+    ///
+    /// # Returns
+    ///
+    /// Whether the plan must be made again: the view shows a new wall on its way, a monster or an opponent
+    /// on its way or next to it, or a new hint or the goal.
+    fn merge_position(&mut self, position: Position, seen: &str) -> bool {
+        let is_on_plan = |plan: &Plan| -> bool {
+            return plan.crosses(position)
+                || DIRECTIONS.iter().any(|&direction: &CardinalDirection| {
+                    let ((row_offset, col_offset), _) = move_offsets(direction);
+                    return plan.crosses((position.0 + row_offset, position.1 + col_offset));
+                });
+        };
+        let mut is_plan_outdated: bool =
+            (seen == "M" || seen == "E") && self.plan.as_ref().is_some_and(is_on_plan);
+
+        let (seen, sighting): (&str, Option<Sighting>) = if is_entity(seen) {
+            let sighting: Sighting = Sighting {
+                marker: String::from(seen),
+                turn: self.turn,
+            };
+            (" ", Some(sighting))
+        } else {
+            (seen, None)
+        };
+        if is_cell(position) {
+            self.sightings.set(position, sighting);
+        }
+
+        let known: &str = self.cell(position.0, position.1);
+        if known == seen {
+            return is_plan_outdated;
+        }
+        let is_contradiction: bool = known != "#";
+        if is_contradiction {
+            log_warning!(
+                "The radar shows '{}' at {:?} where the map has '{}'",
+                seen,
+                position,
+                known
+            );
+            self.contradictions += 1;
+        }
+        if seen == "H" {
+            self.hints.push(position);
+        }
+        is_plan_outdated |= seen == "H"
+            || seen == "G"
+            || ((seen == "-" || seen == "|")
+                && self
+                    .plan
+                    .as_ref()
+                    .is_some_and(|plan: &Plan| plan.crosses(position)));
+        self.grid.set(position, String::from(seen));
+        return is_plan_outdated;
     }
 
     pub fn select_string_to_save<'a>(
@@ -98,12 +442,14 @@ impl Map {
     }
 
     pub fn next_move_random(&mut self) -> Option<(RelativeDirection, CardinalDirection)> {
-        let moves: [(
-            CardinalDirection,
-            RelativeDirection,
-            (isize, isize),
-            (isize, isize),
-        ); 4] = [
+        if let Some(direction) = self
+            .next_planned_move()
+            .or_else(|| self.next_move_to_hint())
+        {
+            return Some(self.choose_move(direction));
+        }
+
+        let moves: [RandomMove; 4] = [
             (
                 CardinalDirection::North,
                 RelativeDirection::Front,
@@ -140,25 +486,33 @@ impl Map {
             let wall_row: isize = player_row + wall_row_offset;
             let wall_col: isize = player_column + wall_col_offset;
 
-            if new_player_row < 0
-                || new_player_column < 0
-                || self.grid.len() as isize <= new_player_row
-                || self.grid[0].len() as isize <= new_player_column
-            {
+            if !self.grid.contains((new_player_row, new_player_column)) {
                 continue;
             }
 
-            let wall: &String = &self.grid[wall_row as usize][wall_col as usize];
+            let wall: &str = self.cell(wall_row, wall_col);
             if wall == "-" || wall == "|" {
                 continue;
             }
 
-            let cell = &self.grid[new_player_row as usize][new_player_column as usize];
+            let cell: &str = self.cell(new_player_row, new_player_column);
             if cell == "•" || cell == "-" || cell == "|" {
                 continue;
             }
 
-            prioritized_moves.push((dir.clone(), rel_dir.clone(), *row_offset, *col_offset));
+            prioritized_moves.push((*dir, *rel_dir, *row_offset, *col_offset));
+        }
+
+        if let Some(safest) = prioritized_moves
+            .iter()
+            .map(|(_, _, row_offset, col_offset)| {
+                self.cell_priority(player_row + row_offset, player_column + col_offset)
+            })
+            .min()
+        {
+            prioritized_moves.retain(|(_, _, row_offset, col_offset)| {
+                self.cell_priority(player_row + row_offset, player_column + col_offset) == safest
+            });
         }
 
         prioritized_moves.sort_by_key(|(_, rel_dir, _, _)| match rel_dir {
@@ -168,154 +522,879 @@ impl Map {
             RelativeDirection::Back => 3,
         });
 
-        if let Some((chosen_dir, chosen_rel_dir, row_offset, col_offset)) =
-            prioritized_moves.choose(&mut thread_rng())
-        {
-            let new_r: isize = player_row + row_offset;
-            let new_c: isize = player_column + col_offset;
-            self.player_position = (new_r, new_c);
-            self.visits[new_r as usize][new_c as usize] += 1;
-
-            self.current_cardinal_direction = chosen_dir.clone();
-            Some((chosen_rel_dir.clone(), chosen_dir.clone()))
+        if let Some((chosen_dir, chosen_rel_dir, _, _)) = prioritized_moves.choose(&mut self.rng) {
+            self.pending_move = Some(*chosen_dir);
+            Some((*chosen_rel_dir, *chosen_dir))
         } else {
             None
         }
     }
 
-    pub fn should_expand_grid(&self, next_cardinal_direction: CardinalDirection) -> bool {
-        let (row_offset, col_offset) = match next_cardinal_direction {
-            CardinalDirection::North => (-2, 0),
-            CardinalDirection::South => (2, 0),
-            CardinalDirection::East => (0, 2),
-            CardinalDirection::West => (0, -2),
-        };
+    /// Chooses a move with Trémaux's algorithm, the passages being marked as the player goes through them
+    /// (see `marks`):
+    ///
+    /// * A passage marked twice is never taken again.
+    /// * Arriving through a new passage into a cell already explored, the player goes back through it.
+    /// * Otherwise, it takes the passage with the fewest marks.
+    ///
+    /// Every passage being taken at most twice, the exploration ends on mazes with loops too. The sealed
+    /// dead ends and the cells an entity makes dangerous are only entered when nothing else is possible.
+    /// If no passage is left, the player heads to the nearest frontier cell, if any, through no other passage
+    /// marked twice when it can.
+    pub fn next_move_tremaux(&mut self) -> Option<(RelativeDirection, CardinalDirection)> {
+        if let Some(direction) = self
+            .next_planned_move()
+            .or_else(|| self.next_move_to_hint())
+        {
+            return Some(self.choose_move(direction));
+        }
 
-        let new_player_pos: (isize, isize) = (
-            self.player_position.0 + row_offset,
-            self.player_position.1 + col_offset,
-        );
+        let (player_row, player_column) = self.player_position;
+        let entry: Option<CardinalDirection> = self.entry_direction();
+        let mut can_go_back: bool = false;
+        let mut is_explored: bool = false;
+        let mut best_move: Option<(CardinalDirection, MoveRank)> = None;
+
+        for direction in DIRECTIONS {
+            let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+                move_offsets(direction);
+            let target: Position = (player_row + row_offset, player_column + col_offset);
+            let wall: Position = (
+                player_row + wall_row_offset,
+                player_column + wall_col_offset,
+            );
+
+            if !self.grid.contains(target) {
+                continue;
+            }
 
-        let grid_rows: isize = self.grid.len() as isize;
-        let grid_cols: isize = self.grid[0].len() as isize;
+            let wall_content: &str = self.cell(wall.0, wall.1);
+            if wall_content == "-" || wall_content == "|" {
+                continue;
+            }
 
-        match next_cardinal_direction {
-            CardinalDirection::North => new_player_pos.0 - 3 < 0,
-            CardinalDirection::South => new_player_pos.0 + 3 >= grid_rows,
-            CardinalDirection::East => new_player_pos.1 + 3 >= grid_cols,
-            CardinalDirection::West => new_player_pos.1 - 3 < 0,
-        }
-    }
+            let cell: &str = self.cell(target.0, target.1);
+            if cell == "•" || cell == "-" || cell == "|" {
+                continue;
+            }
 
-    pub fn expand_grid_if_needed(&mut self) {
-        let grid_rows: isize = self.grid.len() as isize;
-        let grid_cols: isize = self.grid[0].len() as isize;
+            let marks: u8 = self.marks.value(wall);
+            if Some(direction) == entry {
+                can_go_back = marks < 2 && !self.is_sealed(target);
+            } else if 0 < marks {
+                is_explored = true;
+            }
+            if 2 <= marks {
+                continue;
+            }
 
-        let mut expand_top: isize = 0;
-        let mut expand_left: isize = 0;
-        let mut expand_bottom: isize = 0;
-        let mut expand_right: isize = 0;
+            // Monsters and opponents are avoided, but a defeatable monster is attacked first.
+            let priority: u8 = self.cell_priority(target.0, target.1);
+            let rank: MoveRank = (
+                self.is_sealed(target),
+                2 < priority,
+                marks,
+                priority,
+                self.blocked_attempts.value(target),
+            );
+            if best_move.is_none() || rank < best_move.as_ref().unwrap().1 {
+                best_move = Some((direction, rank));
+            }
+        }
 
-        if self.player_position.0 - 3 < 0 {
-            expand_top = 2;
+        if let (true, true, Some(direction)) = (can_go_back, is_explored, entry) {
+            return Some(self.choose_move(direction));
         }
-        if self.player_position.0 + 3 >= grid_rows {
-            expand_bottom = 2;
+        if let Some((direction, _)) = best_move {
+            return Some(self.choose_move(direction));
         }
-        if self.player_position.1 - 3 < 0 {
-            expand_left = 2;
+        // Every passage of the cell is marked twice, or the marks are inconsistent after a respawn or a
+        // detour to a hint. Beyond the cell, the route to the nearest frontier cell avoids the passages
+        // marked twice while another one exists.
+        self.plan = self
+            .route_where(
+                |cell: Position| self.is_frontier(cell),
+                |cell: Position, wall: Position| {
+                    cell == self.player_position || self.marks.value(wall) < 2
+                },
+            )
+            .or_else(|| {
+                self.nearest_frontier()
+                    .and_then(|(frontier, _)| self.route_to(frontier))
+            });
+        return self
+            .next_planned_move()
+            .map(|direction: CardinalDirection| self.choose_move(direction));
+    }
+
+    /// Chooses a move keeping a hand on the walls: the side of `hand` is tried first, then the front, the
+    /// other side and the back. The cells an entity makes dangerous are only entered when nothing else is
+    /// possible.
+    ///
+    /// # Arguments
+    ///
+    /// * `hand` - `Left` or `Right`, any other direction is taken as `Left`.
+    ///
+    /// # Returns
+    ///
+    /// The move relative to the heading of the player and its cardinal direction. `None` if the player
+    /// cannot move, or if the follower is going around an island: it already chose the same move from the
+    /// same cell, `is_follower_looping` is then set and the follower gives up for the rest of the game.
+    pub fn next_move_wall_follower(
+        &mut self,
+        hand: RelativeDirection,
+    ) -> Option<(RelativeDirection, CardinalDirection)> {
+        if self.is_follower_looping {
+            return None;
+        }
+        if let Some(direction) = self
+            .next_planned_move()
+            .or_else(|| self.next_move_to_hint())
+        {
+            return Some(self.choose_move(direction));
+        }
+
+        let order: [RelativeDirection; 4] = match hand {
+            RelativeDirection::Right => [
+                RelativeDirection::Right,
+                RelativeDirection::Front,
+                RelativeDirection::Left,
+                RelativeDirection::Back,
+            ],
+            _ => [
+                RelativeDirection::Left,
+                RelativeDirection::Front,
+                RelativeDirection::Right,
+                RelativeDirection::Back,
+            ],
+        };
+        let (player_row, player_column) = self.player_position;
+        let directions: Vec<CardinalDirection> = order
+            .iter()
+            .map(|&relative_direction: &RelativeDirection| {
+                self.current_cardinal_direction.turn(relative_direction)
+            })
+            .filter(|&direction: &CardinalDirection| {
+                let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+                    move_offsets(direction);
+                let wall: &str = self.cell(
+                    player_row + wall_row_offset,
+                    player_column + wall_col_offset,
+                );
+                return self
+                    .grid
+                    .contains((player_row + row_offset, player_column + col_offset))
+                    && wall != "-"
+                    && wall != "|";
+            })
+            .collect();
+        let direction: CardinalDirection = *directions
+            .iter()
+            .find(|&&direction: &&CardinalDirection| {
+                let ((row_offset, col_offset), _) = move_offsets(direction);
+                return self.cell_priority(player_row + row_offset, player_column + col_offset)
+                    <= 2;
+            })
+            .or(directions.first())?;
+
+        if !self
+            .follower_moves
+            .insert((self.player_position, direction))
+        {
+            log_debug!(
+                "The wall follower goes around an island at {:?}",
+                self.player_position
+            );
+            self.is_follower_looping = true;
+            return None;
         }
-        if self.player_position.1 + 3 >= grid_cols {
-            expand_right = 2;
+        return Some(self.choose_move(direction));
+    }
+
+    /// Chooses a move towards a frontier cell, following a route planned over the known passages.
+    ///
+    /// The route goes to the exit once it is known. Otherwise it goes to the frontier cell with the lowest
+    /// cost: its distance, plus a penalty outside the sector of the player, minus what the radar would
+    /// reveal there (the unknown cells around it) and minus its alignment with the compass. The route is
+    /// kept while its target stays a frontier cell and its next passage stays open. Without any reachable
+    /// target, the player falls back to Trémaux.
+    pub fn next_move_frontier(&mut self) -> Option<(RelativeDirection, CardinalDirection)> {
+        if let Some(direction) = self
+            .next_planned_move()
+            .or_else(|| self.next_move_to_hint())
+        {
+            return Some(self.choose_move(direction));
         }
 
-        let new_rows: isize = grid_rows + expand_top + expand_bottom;
-        let new_cols: isize = grid_cols + expand_left + expand_right;
+        self.plan = self
+            .known_goal()
+            .and_then(|goal: Position| self.route_to(goal))
+            .or_else(|| {
+                self.best_frontier()
+                    .and_then(|target: Position| self.route_to(target))
+            });
+        return match self.next_planned_move() {
+            Some(direction) => Some(self.choose_move(direction)),
+            None => self.next_move_tremaux(),
+        };
+    }
 
-        if new_rows == grid_rows && new_cols == grid_cols {
-            return;
+    /// Returns the next move of the plan, after dropping the plan if it is over, if the player is not where
+    /// it expects, if its next passage is closed or if its target is no longer worth reaching: a hint
+    /// visited or ignored, or a cell no longer on the frontier.
+    fn next_planned_move(&mut self) -> Option<CardinalDirection> {
+        let plan: &Plan = self.plan.as_ref()?;
+        let direction: Option<CardinalDirection> = plan.next_move().filter(|&direction| {
+            return plan.from == self.player_position
+                && (Some(plan.target) == self.known_goal()
+                    || (self.hint_policy != HintPolicy::Ignore
+                        && self.unvisited_hints().contains(&plan.target))
+                    || self.is_frontier(plan.target))
+                && self
+                    .passages(self.player_position)
+                    .iter()
+                    .any(|&(passage, _): &(CardinalDirection, Position)| passage == direction);
+        });
+        if direction.is_none() {
+            self.plan = None;
         }
+        return direction;
+    }
 
-        let mut new_grid: Vec<Vec<String>> =
-            vec![vec![String::from("#"); new_cols as usize]; new_rows as usize];
-        for i in 0..grid_rows {
-            for j in 0..grid_cols {
-                new_grid[(i + expand_top) as usize][(j + expand_left) as usize] =
-                    self.grid[i as usize][j as usize].clone();
+    /// Returns the sector of a cell: the main direction of the cell from the origin of the sectors.
+    pub fn sector_of(&self, (row, column): Position) -> CardinalDirection {
+        let origin: Position = self.sector_origin.unwrap_or(self.spawn_position);
+        let delta_row: isize = row - origin.0;
+        let delta_column: isize = column - origin.1;
+        return if delta_column.abs() <= delta_row.abs() {
+            if delta_row < 0 {
+                CardinalDirection::North
+            } else {
+                CardinalDirection::South
             }
+        } else if 0 < delta_column {
+            CardinalDirection::East
+        } else {
+            CardinalDirection::West
+        };
+    }
+
+    /// Returns the distance from the player to the nearest frontier cell of each sector which has one, the
+    /// bids of the player for a team coordinator.
+    pub fn sector_costs(&self) -> Vec<(CardinalDirection, u32)> {
+        let distances: HashMap<Position, (u32, CardinalDirection)> =
+            self.junction_graph().distances(self.player_position);
+        let mut costs: Vec<(CardinalDirection, u32)> = Vec::with_capacity(4);
+        for sector in DIRECTIONS {
+            let cost: Option<u32> = distances
+                .iter()
+                .filter(|(&position, _): &(&Position, &(u32, CardinalDirection))| {
+                    position != self.player_position
+                        && self.is_frontier(position)
+                        && self.sector_of(position) == sector
+                })
+                .map(|(_, &(distance, _)): (&Position, &(u32, CardinalDirection))| distance)
+                .min();
+            if let Some(cost) = cost {
+                costs.push((sector, cost));
+            }
+        }
+        return costs;
+    }
+
+    /// Returns the frontier cell with the lowest cost, see `next_move_frontier`.
+    fn best_frontier(&self) -> Option<Position> {
+        let distances: HashMap<Position, (u32, CardinalDirection)> =
+            self.junction_graph().distances(self.player_position);
+        let cost = |position: Position, distance: u32| -> f32 {
+            let penalty: f32 = match self.sector {
+                Some(sector) if sector != self.sector_of(position) => SECTOR_PENALTY,
+                _ => 0.0,
+            };
+            return distance as f32 + penalty
+                - INFORMATION_WEIGHT * self.information_gain(position) as f32
+                - self.compass_weight * self.compass_alignment(position);
+        };
+        return distances
+            .iter()
+            .filter(|(&position, _)| position != self.player_position && self.is_frontier(position))
+            .map(|(&position, &(distance, _))| (cost(position, distance), position))
+            .min_by(|a: &(f32, Position), b: &(f32, Position)| {
+                a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+            })
+            .map(|(_, position)| position);
+    }
+
+    /// Counts the unknown cells the radar would reveal around a cell: its 3x3 footprint.
+    fn information_gain(&self, (row, column): Position) -> usize {
+        return (-2..=2)
+            .step_by(2)
+            .flat_map(|row_offset: isize| {
+                (-2..=2)
+                    .step_by(2)
+                    .map(move |col_offset: isize| (row + row_offset, column + col_offset))
+            })
+            .filter(|&(row, column): &Position| self.cell(row, column) == "#")
+            .count();
+    }
+
+    /// Returns the cosine of the angle between the compass and the direction of a cell from the player:
+    /// `1` straight towards the exit, `-1` away from it, `0` without compass.
+    fn compass_alignment(&self, (row, column): Position) -> f32 {
+        let bearing: f32 = match self.goal_bearing {
+            Some(bearing) => bearing,
+            None => return 0.0,
+        };
+        let delta_row: f32 = (row - self.player_position.0) as f32;
+        let delta_column: f32 = (column - self.player_position.1) as f32;
+        if delta_row == 0.0 && delta_column == 0.0 {
+            return 0.0;
         }
+        // Rows grow towards the South, so the North is the opposite of the row axis.
+        let angle: f32 = delta_column.atan2(-delta_row).to_degrees();
+        return (angle - bearing).to_radians().cos();
+    }
 
-        self.player_position.0 += expand_top;
-        self.player_position.1 += expand_left;
+    /// Records a compass hint. The server sends it with the view following a move, so its angle is relative
+    /// to the heading after the pending move.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - The direction of the exit in degrees, `0` straight ahead and `90` on the right.
+    pub fn record_compass(&mut self, angle: f32) {
+        let heading: CardinalDirection =
+            self.pending_move.unwrap_or(self.current_cardinal_direction);
+        self.goal_bearing = Some((heading.degrees() + angle).rem_euclid(360.0));
+    }
 
-        let old_visit_rows: isize = self.visits.len() as isize;
-        let old_visit_cols: isize = self.visits[0].len() as isize;
-        let mut new_visits: Vec<Vec<u32>> = vec![vec![0; new_cols as usize]; new_rows as usize];
-        for i in 0..old_visit_rows {
-            for j in 0..old_visit_cols {
-                new_visits[(i + expand_top) as usize][(j + expand_left) as usize] =
-                    self.visits[i as usize][j as usize];
+    /// Plans a shortest route over the passages from the player to a cell.
+    ///
+    /// # Returns
+    ///
+    /// The plan, or `None` if the cell is out of reach or is the cell of the player.
+    pub fn route_to(&self, target: Position) -> Option<Plan> {
+        return self.route_where(|cell: Position| cell == target, |_, _| true);
+    }
+
+    /// Plans a shortest route over the passages from the player to the nearest cell accepted by
+    /// `is_target`, the cell of the player excepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_target` - Tells if a cell ends the route.
+    /// * `can_cross` - Tells if the route may go through a passage, given the cell it leaves and the
+    ///   position of the wall between the two cells.
+    fn route_where(
+        &self,
+        is_target: impl Fn(Position) -> bool,
+        can_cross: impl Fn(Position, Position) -> bool,
+    ) -> Option<Plan> {
+        let mut previous: HashMap<Position, (Position, CardinalDirection)> = HashMap::new();
+        let mut queue: VecDeque<Position> = VecDeque::from([self.player_position]);
+        while let Some(cell) = queue.pop_front() {
+            if cell != self.player_position && is_target(cell) {
+                let mut moves: Vec<CardinalDirection> = Vec::new();
+                let mut position: Position = cell;
+                while position != self.player_position {
+                    let (before, direction) = previous[&position];
+                    moves.push(direction);
+                    position = before;
+                }
+                moves.reverse();
+                return Some(Plan::new(self.player_position, moves));
+            }
+            for (direction, next) in self.passages(cell) {
+                let wall: Position = ((cell.0 + next.0) / 2, (cell.1 + next.1) / 2);
+                if next == self.player_position || !can_cross(cell, wall) {
+                    continue;
+                }
+                if let Entry::Vacant(entry) = previous.entry(next) {
+                    entry.insert((cell, direction));
+                    queue.push_back(next);
+                }
             }
         }
-        self.visits = new_visits;
-        self.grid = new_grid;
+        return None;
     }
 
-    pub fn next_move_tremaux(&mut self) -> Option<(RelativeDirection, CardinalDirection)> {
-        let moves: [(CardinalDirection, (isize, isize), (isize, isize)); 4] = [
-            (CardinalDirection::North, (-2, 0), (-1, 0)),
-            (CardinalDirection::East, (0, 2), (0, 1)),
-            (CardinalDirection::South, (2, 0), (1, 0)),
-            (CardinalDirection::West, (0, -2), (0, -1)),
-        ];
+    /// Returns the direction, seen from the player, of the passage it came through into its cell. `None` at
+    /// the start or after a respawn.
+    fn entry_direction(&self) -> Option<CardinalDirection> {
+        if self.path.len() < 2 {
+            return None;
+        }
+        let previous: Position = self.path[self.path.len() - 2];
+        return DIRECTIONS
+            .into_iter()
+            .find(|&direction: &CardinalDirection| {
+                let ((row_offset, col_offset), _) = move_offsets(direction);
+                return previous
+                    == (
+                        self.player_position.0 + row_offset,
+                        self.player_position.1 + col_offset,
+                    );
+            });
+    }
 
-        let (player_row, player_column) = self.player_position;
-        let mut best_move: Option<(CardinalDirection, (isize, isize), u32)> = None;
+    /// Makes a move the pending move of the map.
+    ///
+    /// # Returns
+    ///
+    /// The move relative to the heading of the player and its cardinal direction.
+    fn choose_move(
+        &mut self,
+        direction: CardinalDirection,
+    ) -> (RelativeDirection, CardinalDirection) {
+        self.pending_move = Some(direction);
+        return (
+            absolute_to_relative_direction(&self.current_cardinal_direction, &direction),
+            direction,
+        );
+    }
 
-        for (dir, (row_offset, col_offset), (wall_row_offset, wall_col_offset)) in moves.iter() {
-            let new_player_row: isize = player_row + row_offset;
-            let new_player_column: isize = player_column + col_offset;
-            let wall_row: isize = player_row + wall_row_offset;
-            let wall_col: isize = player_column + wall_col_offset;
+    /// Returns the hint cells seen but never entered by the player.
+    pub fn unvisited_hints(&self) -> Vec<(isize, isize)> {
+        return self
+            .hints
+            .iter()
+            .filter(|&&position| self.visits.value(position) == 0)
+            .copied()
+            .collect();
+    }
 
-            if new_player_row < 0
-                || new_player_column < 0
-                || self.grid.len() as isize <= new_player_row
-                || self.grid[0].len() as isize <= new_player_column
+    /// Plans a route to the nearest unvisited hint cell, if the hint policy accepts the detour, and returns
+    /// its first move. The distances go through the passages of the junction graph.
+    fn next_move_to_hint(&mut self) -> Option<CardinalDirection> {
+        let max_distance: u32 = match self.hint_policy {
+            HintPolicy::Ignore => return None,
+            HintPolicy::Opportunistic => HINT_DETOUR,
+            HintPolicy::Always => u32::MAX,
+        };
+        let mut hints: Vec<(isize, isize)> = self.unvisited_hints();
+        hints.retain(|&hint: &(isize, isize)| hint != self.player_position);
+        if hints.is_empty() {
+            return None;
+        }
+
+        let distances: HashMap<Position, (u32, CardinalDirection)> =
+            self.junction_graph().distances(self.player_position);
+        let hint: Position = *hints
+            .iter()
+            .filter(|&hint: &&(isize, isize)| {
+                distances
+                    .get(hint)
+                    .is_some_and(|&(distance, _)| distance <= max_distance)
+            })
+            .min_by_key(|&hint: &&(isize, isize)| (distances[hint].0, *hint))?;
+        self.plan = self.route_to(hint);
+        return self.plan.as_ref().and_then(Plan::next_move);
+    }
+
+    /// Returns the nearest frontier cell through the passages of the junction graph, with the first move
+    /// towards it. The cell of the player is not counted.
+    pub fn nearest_frontier(&self) -> Option<(Position, CardinalDirection)> {
+        let distances: HashMap<Position, (u32, CardinalDirection)> =
+            self.junction_graph().distances(self.player_position);
+        return distances
+            .iter()
+            .filter(|(&position, _)| position != self.player_position && self.is_frontier(position))
+            .min_by_key(|(&position, &(distance, _))| (distance, position))
+            .map(|(&position, &(_, direction))| (position, direction));
+    }
+
+    /// Builds the junction graph of the known cells, the cell of the player being one of its nodes.
+    pub fn junction_graph(&self) -> JunctionGraph {
+        return JunctionGraph::new(self);
+    }
+
+    /// Returns the moves from a cell to the cells next to it which the planners may go through: the wall
+    /// between them is known to be open, the next cell is known, and no monster nor opponent is expected
+    /// around it. A sealed cell is only entered from another sealed cell, to leave a sealed branch.
+    pub fn passages(&self, (row, column): Position) -> Vec<(CardinalDirection, Position)> {
+        let is_in_sealed_branch: bool = self.is_sealed((row, column));
+        let mut passages: Vec<(CardinalDirection, Position)> = Vec::with_capacity(4);
+        for direction in DIRECTIONS {
+            let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+                move_offsets(direction);
+            let next: Position = (row + row_offset, column + col_offset);
+            if self.cell(row + wall_row_offset, column + wall_col_offset) == " "
+                && self.cell(next.0, next.1) != "#"
+                && (is_in_sealed_branch || !self.is_sealed(next))
+                && self.cell_priority(next.0, next.1) <= 2
             {
-                continue;
+                passages.push((direction, next));
             }
+        }
+        return passages;
+    }
 
-            let wall: &String = &self.grid[wall_row as usize][wall_col as usize];
-            if wall == "-" || wall == "|" {
+    /// Tells if a known cell is open towards an unknown cell.
+    pub fn is_frontier(&self, (row, column): Position) -> bool {
+        if self.cell(row, column) == "#" {
+            return false;
+        }
+        return DIRECTIONS.iter().any(|&direction: &CardinalDirection| {
+            let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+                move_offsets(direction);
+            let wall: &str = self.cell(row + wall_row_offset, column + wall_col_offset);
+            return wall != "-"
+                && wall != "|"
+                && self.cell(row + row_offset, column + col_offset) == "#";
+        });
+    }
+
+    pub fn is_sealed(&self, position: Position) -> bool {
+        return self.sealed.value(position);
+    }
+
+    /// Seals every explored dead end of the map, forgetting the previous seals.
+    ///
+    /// A dead end is a known cell whose walls are all known and which opens to at most one cell which is
+    /// not sealed. It is sealed unless the player is on it, or it is the exit or an unvisited hint.
+    /// Sealing a cell may turn the cell before it into a dead end: a whole explored branch is sealed.
+    pub fn seal_dead_ends(&mut self) {
+        self.sealed = SparseGrid::new(|_| false);
+        let cells: Vec<Position> = self
+            .grid
+            .positions()
+            .filter(|&position: &Position| is_cell(position))
+            .collect();
+        self.seal_dead_ends_from(cells);
+    }
+
+    /// Seals the dead ends among some cells, then the cells which become dead ends because of them.
+    fn seal_dead_ends_from(&mut self, mut cells: Vec<Position>) {
+        while let Some(cell) = cells.pop() {
+            if self.is_sealed(cell) || !self.is_dead_end(cell) {
                 continue;
             }
+            self.sealed.set(cell, true);
+            for direction in DIRECTIONS {
+                let ((row_offset, col_offset), _) = move_offsets(direction);
+                cells.push((cell.0 + row_offset, cell.1 + col_offset));
+            }
+        }
+    }
 
-            let cell = &self.grid[new_player_row as usize][new_player_column as usize];
-            if cell == "•" || cell == "-" || cell == "|" {
-                continue;
+    fn is_dead_end(&self, (row, column): Position) -> bool {
+        if (row, column) == self.player_position {
+            return false;
+        }
+        match self.cell(row, column) {
+            "#" | "G" => return false,
+            "H" if self.visits.value((row, column)) == 0 => return false,
+            _ => {}
+        }
+
+        let mut exits: usize = 0;
+        for direction in DIRECTIONS {
+            let ((row_offset, col_offset), (wall_row_offset, wall_col_offset)) =
+                move_offsets(direction);
+            match self.cell(row + wall_row_offset, column + wall_col_offset) {
+                "#" => return false,
+                "-" | "|" => {}
+                _ => {
+                    if !self.is_sealed((row + row_offset, column + col_offset)) {
+                        exits += 1;
+                    }
+                }
             }
+        }
+        return exits <= 1;
+    }
 
-            let visits = self.visits[new_player_row as usize][new_player_column as usize];
-            if best_move.is_none() || visits < best_move.as_ref().unwrap().2 {
-                best_move = Some((dir.clone(), (*row_offset, *col_offset), visits));
+    /// Merges the first radar view of a session resumed after a lost connection.
+    ///
+    /// The response to the pending move, if any, may have been lost with the connection: the move is
+    /// accepted when the view fits the map better from the cell it leads to, and cancelled otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded_radar` - The radar view sent by the server, still encoded since its orientation depends
+    ///   on whether the move was made.
+    ///
+    /// # Returns
+    ///
+    /// The direction the player faces.
+    pub fn resume(&mut self, encoded_radar: &str) -> CardinalDirection {
+        let view: Vec<Vec<String>> =
+            RadarView::new(String::from(encoded_radar), self.current_cardinal_direction).grid;
+        if let Some(move_direction) = self.pending_move {
+            let moved_view: Vec<Vec<String>> =
+                RadarView::new(String::from(encoded_radar), move_direction).grid;
+            let ((row_offset, col_offset), _) = move_offsets(move_direction);
+            let target: Position = (
+                self.player_position.0 + row_offset,
+                self.player_position.1 + col_offset,
+            );
+            if self.compare_view(target, &moved_view).0
+                < self.compare_view(self.player_position, &view).0
+            {
+                log_debug!("The lost move {:?} was made", move_direction);
+                self.commit_move(&moved_view);
+                return move_direction;
             }
+            self.pending_move = None;
+            self.plan = None;
         }
+        self.merge_radar_view_to_map_grid(&view);
+        return self.current_cardinal_direction;
+    }
 
-        if let Some((chosen_dir, (row_offset, col_offset), _)) = best_move {
-            let new_r: isize = player_row + row_offset;
-            let new_c: isize = player_column + col_offset;
-            self.player_position = (new_r, new_c);
-            self.visits[new_r as usize][new_c as usize] += 1;
+    /// Starts a new game on this map, saved at the end of a game on the same maze. The map is anchored
+    /// where the first radar view of the new game fits: on the saved spawn when the view fits there, else
+    /// on the only cell where it fits. The player stands there, facing North.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_game` - The map of the new game, made from its first radar view.
+    ///
+    /// # Returns
+    ///
+    /// `false`, leaving the map unchanged, when the view fits nowhere or on several cells.
+    pub fn restart(&mut self, new_game: &Map) -> bool {
+        let spawn: Position = match self.locate(new_game, Some(self.spawn_position)) {
+            Some(spawn) => spawn,
+            None => return false,
+        };
+        self.spawn_position = spawn;
+        self.respawn();
+        self.merge_radar_view_to_map_grid(&new_game.view_around(new_game.player_position));
+        return true;
+    }
 
-            let relative_direction: RelativeDirection =
-                absolute_to_relative_direction(&self.current_cardinal_direction, &chosen_dir);
-            self.current_cardinal_direction = chosen_dir;
-            Some((relative_direction, self.current_cardinal_direction.clone()))
-        } else {
-            return None;
+    /// Finds where the player of another map of the same maze stands on this map: the cell where the part
+    /// of the other map the radar shows around that player fits.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other map, whose positions do not match those of this map.
+    /// * `guess` - A cell taken whenever the view fits there.
+    ///
+    /// # Returns
+    ///
+    /// The cell, or `None` when the view fits nowhere or on several cells.
+    pub fn locate(&self, other: &Map, guess: Option<Position>) -> Option<Position> {
+        let view: Vec<Vec<String>> = other.view_around(other.player_position);
+        let shown: usize = view
+            .iter()
+            .flatten()
+            .filter(|seen: &&String| *seen != "#")
+            .count();
+        // The map must know at least half of what the view shows, or the view would fit anywhere.
+        let fits = |center: Position| -> bool {
+            let (conflicts, matches) = self.compare_view(center, &view);
+            return conflicts == 0 && shown <= 2 * matches;
+        };
+
+        if let Some(guess) = guess.filter(|&guess: &Position| fits(guess)) {
+            return Some(guess);
         }
+        let candidates: Vec<Position> = self
+            .grid
+            .positions()
+            .filter(|&position: &Position| is_cell(position) && fits(position))
+            .collect();
+        return match candidates.as_slice() {
+            [position] => Some(*position),
+            _ => None,
+        };
+    }
+
+    /// Returns the part of the map a radar view centered on a cell would show.
+    fn view_around(&self, (row, column): Position) -> Vec<Vec<String>> {
+        return (row - 3..=row + 3)
+            .map(|row: isize| {
+                (column - 3..=column + 3)
+                    .map(|column: isize| String::from(self.cell(row, column)))
+                    .collect()
+            })
+            .collect();
     }
+
+    /// Learns the walls and cells known by another map of the same maze, what this map knows being kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other map.
+    /// * `offset` - What to add to a position of the other map to get the same position on this map.
+    pub fn learn_from(&mut self, other: &Map, (row_offset, col_offset): Position) {
+        for (row, column) in other.grid.positions() {
+            let content: &str = other.cell(row, column);
+            let position: Position = (row + row_offset, column + col_offset);
+            if content != "#" && self.cell(position.0, position.1) == "#" {
+                self.grid.cover(position, position);
+                self.grid.set(position, String::from(content));
+            }
+        }
+        self.seal_dead_ends();
+    }
+
+    /// Compares a radar view centered on a cell with the map.
+    ///
+    /// # Returns
+    ///
+    /// The number of positions where the view contradicts the map, then the number where they agree. The
+    /// positions unknown to either of them are not counted.
+    fn compare_view(&self, center: Position, view: &[Vec<String>]) -> (usize, usize) {
+        let half: isize = (view.len() / 2) as isize;
+        let mut conflicts: usize = 0;
+        let mut matches: usize = 0;
+        for (i, view_row) in view.iter().enumerate() {
+            for (j, seen) in view_row.iter().enumerate() {
+                let position: Position =
+                    (center.0 - half + i as isize, center.1 - half + j as isize);
+                let seen: &str = if is_entity(seen) { " " } else { seen };
+                let known: &str = self.cell(position.0, position.1);
+                if seen == "#" || known == "#" {
+                    continue;
+                }
+                if seen == known {
+                    matches += 1;
+                } else {
+                    conflicts += 1;
+                }
+            }
+        }
+        return (conflicts, matches);
+    }
+
+    /// Puts the player back on its spawn, facing North, after a monster has defeated it.
+    pub fn respawn(&mut self) {
+        self.pending_move = None;
+        self.plan = None;
+        self.player_position = self.spawn_position;
+        self.current_cardinal_direction = CardinalDirection::North;
+        self.path.push(self.player_position);
+    }
+
+    /// Counts the cells whose content is known, whether they were seen by the radar or visited.
+    pub fn discovered_cells(&self) -> usize {
+        return self
+            .grid
+            .positions()
+            .filter(|&(row, column): &Position| {
+                is_cell((row, column)) && self.cell(row, column) != "#"
+            })
+            .count();
+    }
+
+    /// Returns the position of the exit in the grid, if the radar has seen it.
+    pub fn known_goal(&self) -> Option<(isize, isize)> {
+        return self
+            .grid
+            .positions()
+            .find(|&(row, column): &Position| self.cell(row, column) == "G");
+    }
+
+    /// Returns the known cells which are open towards an unknown cell: where the exploration can go on.
+    pub fn frontier_cells(&self) -> Vec<(isize, isize)> {
+        return self
+            .grid
+            .positions()
+            .filter(|&position: &Position| is_cell(position) && self.is_frontier(position))
+            .collect();
+    }
+
+    /// Returns the marker of the entity expected on a cell: `A`, `E` or `M`.
+    /// An entity is no longer expected a few views after it was last seen.
+    pub fn entity(&self, row: isize, column: isize) -> Option<&str> {
+        return self
+            .sightings
+            .get((row, column))
+            .and_then(|sighting: &Option<Sighting>| sighting.as_ref())
+            .filter(|sighting: &&Sighting| self.turn - sighting.turn <= ENTITY_MEMORY)
+            .map(|sighting: &Sighting| sighting.marker.as_str());
+    }
+
+    /// Returns how many times the player has entered its current cell.
+    pub fn visits_at_player(&self) -> u32 {
+        return self.visits.value(self.player_position);
+    }
+
+    /// Ranks a reachable cell according to the entities around, the lower the better:
+    ///
+    /// * `0` - A monster with enough allies next to it: attacking it defeats it.
+    /// * `1` - Next to a monster while an ally is in sight: converging lets the team attack it.
+    /// * `2` - No monster around.
+    /// * `3` - Next to a monster without any ally in sight.
+    /// * `4` - An opponent: a temporary obstacle, the cell is tried again when nothing else is possible.
+    /// * `5` - A monster the player would face alone. Only tried as a last resort, the marker may be stale.
+    pub fn cell_priority(&self, row: isize, column: isize) -> u8 {
+        match self.entity(row, column) {
+            Some("M") => {
+                let fighters: usize = 1 + self.count_next_to(row, column, "A");
+                return if self.monster_group_size as usize <= fighters {
+                    0
+                } else {
+                    5
+                };
+            }
+            Some("E") => return 4,
+            _ => {}
+        }
+
+        if 0 < self.count_next_to(row, column, "M") {
+            return if self.is_ally_in_sight() { 1 } else { 3 };
+        }
+
+        return 2;
+    }
+
+    /// Counts the cells sharing a side with a cell where the entity `marker` is expected.
+    fn count_next_to(&self, row: isize, column: isize, marker: &str) -> usize {
+        return [(-2, 0), (0, 2), (2, 0), (0, -2)]
+            .iter()
+            .filter(|(row_offset, col_offset)| {
+                self.entity(row + row_offset, column + col_offset) == Some(marker)
+            })
+            .count();
+    }
+
+    /// Tells if an ally is in the range of the radar.
+    fn is_ally_in_sight(&self) -> bool {
+        let (player_row, player_column) = self.player_position;
+        return (player_row - 3..=player_row + 3).any(|row: isize| {
+            (player_column - 3..=player_column + 3)
+                .any(|column: isize| self.entity(row, column) == Some("A"))
+        });
+    }
+
+    /// Returns the content of a position of the grid, unknown (`#` or `•` for a corner) if never seen.
+    pub fn cell(&self, row: isize, column: isize) -> &str {
+        return match self.grid.get((row, column)) {
+            Some(content) => content.as_str(),
+            None => unknown_content((row, column)),
+        };
+    }
+}
+
+/// Tells if a position of the grid is a cell, rather than a wall or a corner.
+pub fn is_cell((row, column): Position) -> bool {
+    return row.rem_euclid(2) == 1 && column.rem_euclid(2) == 1;
+}
+
+/// Returns the content of a position never seen: a corner or an unknown cell or wall.
+fn unknown_content((row, column): Position) -> &'static str {
+    return if row.rem_euclid(2) == 0 && column.rem_euclid(2) == 0 {
+        "•"
+    } else {
+        "#"
+    };
+}
+
+fn is_entity(content: &str) -> bool {
+    return matches!(content, "A" | "E" | "M");
+}
+
+/// Returns the offsets, in the grid, of the cell and of the wall next to the player in a direction.
+pub(crate) fn move_offsets(direction: CardinalDirection) -> ((isize, isize), (isize, isize)) {
+    return match direction {
+        CardinalDirection::North => ((-2, 0), (-1, 0)),
+        CardinalDirection::East => ((0, 2), (0, 1)),
+        CardinalDirection::South => ((2, 0), (1, 0)),
+        CardinalDirection::West => ((0, -2), (0, -1)),
+    };
 }
 
 fn absolute_to_relative_direction(
@@ -348,6 +1427,8 @@ fn absolute_to_relative_direction(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::strategy::Strategy;
+    use shared::types::radar_item::RadarItem;
     use shared::utils::{print_string_matrix, string_to_strings};
 
     // #[test]
@@ -421,94 +1502,11 @@ mod tests {
     // }
 
     #[test]
-    fn test_should_expand_grid() {
-        let grid_1: Vec<Vec<String>> = vec![
-            string_to_strings("•-•-•-•"),
-            string_to_strings("|1|2|3|"),
-            string_to_strings("•-•-•-•"),
-            string_to_strings("|4|5|6|"),
-            string_to_strings("•-•-•-•"),
-            string_to_strings("|7|8|9|"),
-            string_to_strings("•-•-•-•"),
-        ];
-        let mut map_1: Map = Map::new(&grid_1, CardinalDirection::North);
-        map_1.player_position = (3, 3);
-        assert_eq!(map_1.should_expand_grid(CardinalDirection::North), true);
-        assert_eq!(map_1.should_expand_grid(CardinalDirection::East), true);
-        assert_eq!(map_1.should_expand_grid(CardinalDirection::South), true);
-        assert_eq!(map_1.should_expand_grid(CardinalDirection::West), true);
-
-        let grid_2: Vec<Vec<String>> = vec![
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-        ];
-        let mut map_2: Map = Map::new(&grid_2, CardinalDirection::North);
-        map_2.player_position = (7, 7);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), false);
-        map_2.player_position = (1, 1);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), true);
-        map_2.player_position = (1, 13);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), false);
-        map_2.player_position = (13, 1);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), true);
-        map_2.player_position = (13, 13);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), false);
-        map_2.player_position = (3, 3);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), true);
-        map_2.player_position = (3, 11);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), false);
-        map_2.player_position = (11, 3);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), true);
-        map_2.player_position = (11, 11);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::North), false);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::East), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::South), true);
-        assert_eq!(map_2.should_expand_grid(CardinalDirection::West), false);
-    }
-
-    #[test]
-    fn test_expand_grid_if_needed() {
-        let grid_1: Vec<Vec<String>> = vec![
+    fn test_grow_without_shifting() {
+        let grid: Vec<Vec<String>> = vec![
             string_to_strings("•-•-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
+            string_to_strings("• •-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
             string_to_strings("•-•-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
@@ -522,49 +1520,12 @@ mod tests {
             string_to_strings("|1|2|3|4|5|6|7|"),
             string_to_strings("•-•-•-•-•-•-•-•"),
         ];
-
         let north_grid: Vec<Vec<String>> = vec![
             string_to_strings("•#•#•#•#•#•#•#•"),
             string_to_strings("###############"),
             string_to_strings("•-•-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
-        ];
-
-        let east_grid: Vec<Vec<String>> = vec![
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-            string_to_strings("|1|2|3|4|5|6|7|##"),
-            string_to_strings("•-•-•-•-•-•-•-•#•"),
-        ];
-
-        let south_grid: Vec<Vec<String>> = vec![
-            string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("|1|2|3|4|5|6|7|"),
-            string_to_strings("•-•-•-•-•-•-•-•"),
+            string_to_strings("• •-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
             string_to_strings("•-•-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
@@ -577,69 +1538,30 @@ mod tests {
             string_to_strings("•-•-•-•-•-•-•-•"),
             string_to_strings("|1|2|3|4|5|6|7|"),
             string_to_strings("•-•-•-•-•-•-•-•"),
-            string_to_strings("###############"),
-            string_to_strings("•#•#•#•#•#•#•#•"),
         ];
-
-        let west_grid: Vec<Vec<String>> = vec![
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-            string_to_strings("##|1|2|3|4|5|6|7|"),
-            string_to_strings("•#•-•-•-•-•-•-•-•"),
-        ];
-        let mut map_1: Map = Map::new(&grid_1, CardinalDirection::North);
-        let mut map_2: Map = Map::new(&grid_1, CardinalDirection::East);
-        let mut map_3: Map = Map::new(&grid_1, CardinalDirection::South);
-        let mut map_4: Map = Map::new(&grid_1, CardinalDirection::West);
-
-        map_1.player_position = (3, 3);
-        map_2.player_position = (12, 12);
-        map_3.player_position = (12, 12);
-        map_4.player_position = (3, 3);
-
-        assert_eq!(map_1.grid.len(), 15);
-        assert_eq!(map_2.grid.len(), 15);
-        assert_eq!(map_3.grid.len(), 15);
-        assert_eq!(map_4.grid.len(), 15);
-
-        assert_eq!(map_1.grid[0].len(), 15);
-        assert_eq!(map_2.grid[0].len(), 15);
-        assert_eq!(map_3.grid[0].len(), 15);
-        assert_eq!(map_4.grid[0].len(), 15);
-
-        map_1.expand_grid_if_needed();
-        map_2.expand_grid_if_needed();
-        map_3.expand_grid_if_needed();
-        map_4.expand_grid_if_needed();
-
-        print_string_matrix("north grid", &north_grid);
-        log_debug!("map_1.grid.len() = {}", map_1.grid.len());
-
-        assert_eq!(map_1.grid.len(), 17);
-        assert_eq!(map_2.grid.len(), 15);
-        assert_eq!(map_3.grid.len(), 17);
-        assert_eq!(map_4.grid.len(), 15);
-
-        assert_eq!(map_1.grid[0].len(), 15);
-        assert_eq!(map_2.grid[0].len(), 17);
-        assert_eq!(map_3.grid[0].len(), 15);
-        assert_eq!(map_4.grid[0].len(), 17);
-
-        assert_eq!(map_1.grid, north_grid);
-        assert_eq!(map_2.grid, east_grid);
-        assert_eq!(map_3.grid, south_grid);
-        assert_eq!(map_4.grid, west_grid);
+        let unknown_view: Vec<Vec<String>> = vec![string_to_strings("#######"); 7];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.player_position, (7, 7));
+        map.player_position = (3, 3);
+        map.hints.push((3, 1));
+
+        // The view seen after moving North goes 2 rows above the grid.
+        map.merge_radar_view(&unknown_view, CardinalDirection::North);
+        assert_eq!(map.player_position, (1, 3));
+        assert_eq!(map.grid.bounds(), Some(((-2, 0), (14, 14))));
+        assert_eq!(map.grid.to_rows(), north_grid);
+
+        // Then 2 columns on the left of it, the known positions stay where they were.
+        map.merge_radar_view(&unknown_view, CardinalDirection::West);
+        assert_eq!(map.player_position, (1, 1));
+        assert_eq!(map.grid.bounds(), Some(((-2, -2), (14, 14))));
+        assert_eq!(map.cell(1, 1), "1");
+        assert_eq!(map.cell(-1, -1), "#");
+        assert_eq!(map.cell(-2, -2), "•");
+        assert_eq!(map.visits.value((1, 1)), 1);
+        assert_eq!(map.spawn_position, (7, 7));
+        assert_eq!(map.path, vec![(7, 7), (1, 3), (1, 1)]);
+        assert_eq!(map.hints, vec![(3, 1)]);
     }
 
     // #[test]
@@ -695,12 +1617,16 @@ mod tests {
 
         print_string_matrix("radar view 1", &radar_1);
         let mut map: Map = Map::new(&radar_1, CardinalDirection::North);
-        print_string_matrix("map + radar view 1", &map.grid);
+        print_string_matrix("map + radar view 1", &map.grid.to_rows());
         assert_eq!(map.player_position, (3, 3));
-        assert_eq!(radar_1, map.grid);
-        let map_with_radar_1_rows_number: usize = map.grid.len();
+        // The ally is a sighting, not a part of the grid.
+        let mut static_radar_1: Vec<Vec<String>> = radar_1.clone();
+        static_radar_1[5][5] = String::from(" ");
+        assert_eq!(static_radar_1, map.grid.to_rows());
+        assert_eq!(map.entity(5, 5), Some("A"));
+        let map_with_radar_1_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_1_columns_number: usize = if 0 < map_with_radar_1_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -717,25 +1643,27 @@ mod tests {
             string_to_strings("| ###  "),
             string_to_strings("• ###-•"),
         ];
+        // The unknown corners of the first view stay unknown, the new column is drawn with its corners.
         let expected_grid_2: Vec<Vec<String>> = vec![
-            string_to_strings("•#•#•#•#•"),
+            string_to_strings("•########"),
             string_to_strings("#########"),
             string_to_strings("•-•-•-•-•"),
             string_to_strings("         "),
             string_to_strings("• •-• •-•"),
-            string_to_strings("| ##|  A "),
-            string_to_strings("•#•#•-•-•"),
+            string_to_strings("| ##|    "),
+            string_to_strings("• ##•-•-•"),
         ];
         print_string_matrix("radar view 2", &radar_2);
         map.merge_radar_view(&radar_2, CardinalDirection::West);
-        print_string_matrix("map + radar view 2", &map.grid);
+        print_string_matrix("map + radar view 2", &map.grid.to_rows());
         log_debug!("{:?}", map.player_position);
-        assert_eq!(map.player_position, (3, 3));
+        assert_eq!(map.player_position, (3, 1));
         // print_string_matrix("map", matrix);
-        assert_eq!(map.grid, expected_grid_2);
-        let map_with_radar_2_rows_number: usize = map.grid.len();
+        assert_eq!(map.grid.to_rows(), expected_grid_2);
+        assert_eq!(map.entity(5, 5), Some("A"));
+        let map_with_radar_2_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_2_columns_number: usize = if 0 < map_with_radar_2_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -754,12 +1682,12 @@ mod tests {
         ];
         print_string_matrix("radar view 3", &radar_3);
         map.merge_radar_view(&radar_3, CardinalDirection::East);
-        print_string_matrix("map + radar view 3", &map.grid);
+        print_string_matrix("map + radar view 3", &map.grid.to_rows());
         log_debug!("{:?}", map.player_position);
-        assert_eq!(map.player_position, (3, 5));
-        let map_with_radar_3_rows_number: usize = map.grid.len();
+        assert_eq!(map.player_position, (3, 3));
+        let map_with_radar_3_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_3_columns_number: usize = if 0 < map_with_radar_3_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -778,11 +1706,11 @@ mod tests {
         ];
         print_string_matrix("radar view 4", &radar_4);
         map.merge_radar_view(&radar_4, CardinalDirection::East);
-        print_string_matrix("map + radar view 4", &map.grid);
-        assert_eq!(map.player_position, (3, 7));
-        let map_with_radar_4_rows_number: usize = map.grid.len();
+        print_string_matrix("map + radar view 4", &map.grid.to_rows());
+        assert_eq!(map.player_position, (3, 5));
+        let map_with_radar_4_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_4_columns_number: usize = if 0 < map_with_radar_4_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -801,11 +1729,11 @@ mod tests {
         ];
         print_string_matrix("radar view 5", &radar_5);
         map.merge_radar_view(&radar_5, CardinalDirection::South);
-        print_string_matrix("map + radar view 5", &map.grid);
-        assert_eq!(map.player_position, (5, 7));
-        let map_with_radar_5_rows_number: usize = map.grid.len();
+        print_string_matrix("map + radar view 5", &map.grid.to_rows());
+        assert_eq!(map.player_position, (5, 5));
+        let map_with_radar_5_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_5_columns_number: usize = if 0 < map_with_radar_5_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -824,11 +1752,11 @@ mod tests {
         ];
         print_string_matrix("radar view 6", &radar_6);
         map.merge_radar_view(&radar_6, CardinalDirection::North);
-        print_string_matrix("map + radar view 6", &map.grid);
-        assert_eq!(map.player_position, (3, 7));
-        let map_with_radar_6_rows_number: usize = map.grid.len();
+        print_string_matrix("map + radar view 6", &map.grid.to_rows());
+        assert_eq!(map.player_position, (3, 5));
+        let map_with_radar_6_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_6_columns_number: usize = if 0 < map_with_radar_6_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -847,11 +1775,11 @@ mod tests {
         ];
         print_string_matrix("radar view 7", &radar_7);
         map.merge_radar_view(&radar_7, CardinalDirection::North);
-        print_string_matrix("map + radar view 7", &map.grid);
-        assert_eq!(map.player_position, (3, 7));
-        let map_with_radar_7_rows_number: usize = map.grid.len();
+        print_string_matrix("map + radar view 7", &map.grid.to_rows());
+        assert_eq!(map.player_position, (1, 5));
+        let map_with_radar_7_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_7_columns_number: usize = if 0 < map_with_radar_7_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
@@ -870,33 +1798,72 @@ mod tests {
         ];
         print_string_matrix("radar view 8", &radar_8);
         map.merge_radar_view(&radar_8, CardinalDirection::South);
-        print_string_matrix("map + radar view 8", &map.grid);
-        assert_eq!(map.player_position, (5, 7));
-        let map_with_radar_8_rows_number: usize = map.grid.len();
+        print_string_matrix("map + radar view 8", &map.grid.to_rows());
+        assert_eq!(map.player_position, (3, 5));
+        let map_with_radar_8_rows_number: usize = map.grid.to_rows().len();
         let map_with_radar_8_columns_number: usize = if 0 < map_with_radar_8_rows_number {
-            map.grid[0].len()
+            map.grid.to_rows()[0].len()
         } else {
             0
         };
         assert_eq!(map_with_radar_8_rows_number, 11);
         assert_eq!(map_with_radar_8_columns_number, 11);
         let expected_final_grid: Vec<Vec<String>> = vec![
-            string_to_strings("####•-• •-•"),
-            string_to_strings("####|    A|"),
-            string_to_strings("######• •##"),
+            string_to_strings("•#•#•-• •-•"),
+            string_to_strings("####|     |"),
+            string_to_strings("•#####• •#•"),
             string_to_strings("######| |##"),
-            string_to_strings("•-•-•-• •##"),
+            string_to_strings("•-•-•-• •#•"),
             string_to_strings("        |##"),
             string_to_strings("• • •-• •-•"),
-            string_to_strings("| |  A     "),
+            string_to_strings("| |        "),
             string_to_strings("• •-•-•-•-•"),
             string_to_strings("###########"),
-            string_to_strings("###########"),
+            string_to_strings("•#•#•#•#•#•"),
         ];
 
         print_string_matrix("expected final grid", &expected_final_grid);
 
-        assert_eq!(map.grid, expected_final_grid);
+        assert_eq!(map.grid.to_rows(), expected_final_grid);
+        assert_eq!(map.entity(-1, 7), Some("A"));
+        assert_eq!(map.entity(5, 3), Some("A"));
+        assert_eq!(map.entity(5, 1), None);
+        // The views of this test disagree on a few walls, the newest ones won.
+        assert!(0 < map.contradictions);
+    }
+
+    #[test]
+    fn test_merge_keeps_static_knowledge() {
+        let radar: Vec<Vec<String>> = vec![
+            string_to_strings("#######"),
+            string_to_strings("#######"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("       "),
+            string_to_strings("•-• •-•"),
+            string_to_strings("##|  A "),
+            string_to_strings("##•-•-•"),
+        ];
+        let mut map: Map = Map::new(&radar, CardinalDirection::North);
+        let static_grid: Vec<Vec<String>> = map.grid.to_rows();
+        assert_eq!(map.frontier_cells(), vec![(3, 1), (3, 5), (5, 5)]);
+
+        // An occluded view teaches nothing and forgets nothing, the ally fades away.
+        let occluded: Vec<Vec<String>> = vec![string_to_strings("#######"); 7];
+        map.merge_radar_view_to_map_grid(&occluded);
+        map.merge_radar_view_to_map_grid(&occluded);
+        assert_eq!(map.grid.to_rows(), static_grid);
+        assert_eq!(map.entity(5, 5), Some("A"));
+        map.merge_radar_view_to_map_grid(&occluded);
+        assert_eq!(map.entity(5, 5), None);
+        assert_eq!(map.contradictions, 0);
+
+        // A wall where the corridor was known to be open.
+        let mut contradicting: Vec<Vec<String>> = occluded.clone();
+        contradicting[3][0] = String::from("|");
+        map.merge_radar_view_to_map_grid(&contradicting);
+        assert_eq!(map.contradictions, 1);
+        assert_eq!(map.cell(3, 0), "|");
+        assert_eq!(map.frontier_cells(), vec![(3, 5), (5, 5)]);
     }
 
     // #[test]
@@ -916,7 +1883,7 @@ mod tests {
 
     //     print_string_matrix("radar view 1", radar_view_1.grid.as_ref());
     //     let mut map: Map = Map::new(&radar_view_1.grid, CardinalDirection::North);
-    //     print_string_matrix("map + radar view 1", &map.grid);
+    //     print_string_matrix("map + radar view 1", &map.grid.to_rows());
     //     assert_eq!(map.player_position, (3, 3));
     //     assert_eq!(radar_1, radar_view_1.grid);
 
@@ -948,38 +1915,598 @@ mod tests {
     //     let map_2: Map = Map::new(&radar_view_2.grid, CardinalDirection::North);
     //     assert_eq!(map_2.grid, radar_view_2.grid);
     //     map.merge_radar_view(&radar_view_2.grid, CardinalDirection::North);
-    //     print_string_matrix("map + radar view 2", &map.grid);
+    //     print_string_matrix("map + radar view 2", &map.grid.to_rows());
     //     print_string_matrix("map + radar view 2 expected", &expected_grid_2);
     //     assert_eq!(map.player_position, (3, 3));
-    //     // assert_eq!(map.grid, radar_view_2.grid);
-    //     assert_eq!(map.grid, expected_grid_2);
+    //     // assert_eq!(map.grid.to_rows(), radar_view_2.grid);
+    //     assert_eq!(map.grid.to_rows(), expected_grid_2);
     // }
 
     #[test]
     fn test_tremaux_algorithm() {
         let grid: Vec<Vec<String>> = vec![
-            string_to_strings("•-•-•"),
-            string_to_strings("| | |"),
-            string_to_strings("•-•-•"),
-            string_to_strings("| | |"),
-            string_to_strings("•-•-•"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | | |"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | | |"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | | |"),
+            string_to_strings("•-•-•-•"),
         ];
         let mut map: Map = Map::new(&grid, CardinalDirection::North);
-        map.player_position = (2, 2);
-        map.current_cardinal_direction = CardinalDirection::North;
 
         assert_eq!(map.next_move_tremaux(), Option::None);
 
-        map.grid[2][4] = String::from(" ");
-        map.visits[2][4] = 0;
+        map.grid.set((3, 4), String::from(" "));
 
+        // The move is only proposed: the map changes once the server has accepted it.
         match map.next_move_tremaux() {
             Some((relative_direction, chosen_cardinal_direction)) => {
                 assert_eq!(relative_direction, RelativeDirection::Right);
                 assert_eq!(chosen_cardinal_direction, CardinalDirection::East);
-                assert_eq!(map.player_position, (2, 4));
+                assert_eq!(map.player_position, (3, 3));
+                assert_eq!(map.visits.value((3, 5)), 0);
             }
             None => panic!("Expected a move."),
         }
+
+        map.commit_move(&grid);
+        assert_eq!(map.player_position, (3, 5));
+        assert_eq!(map.current_cardinal_direction, CardinalDirection::East);
+        assert_eq!(map.visits.value((3, 5)), 1);
+        assert_eq!(map.pending_move, None);
+    }
+
+    #[test]
+    fn test_tremaux_marks() {
+        // A loop of four cells, the player in the bottom left one.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•"),
+            string_to_strings("|   |"),
+            string_to_strings("• • •"),
+            string_to_strings("|   |"),
+            string_to_strings("•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (3, 1);
+        map.path = vec![(3, 1)];
+
+        let mut directions: Vec<CardinalDirection> = Vec::new();
+        while let Some((_, direction)) = map.next_move_tremaux() {
+            directions.push(direction);
+            map.accept_move();
+            assert!(directions.len() <= 8, "The player loops: {:?}", directions);
+        }
+        // Back to a cell already explored through a new passage, the player turns around. Then it goes
+        // back through the passages marked once.
+        assert_eq!(
+            directions,
+            vec![
+                CardinalDirection::North,
+                CardinalDirection::East,
+                CardinalDirection::South,
+                CardinalDirection::West,
+                CardinalDirection::East,
+                CardinalDirection::North,
+                CardinalDirection::West,
+                CardinalDirection::South,
+            ]
+        );
+        assert_eq!(map.player_position, (3, 1));
+        for wall in [(2, 1), (1, 2), (2, 3), (3, 2)] {
+            assert_eq!(map.marks.value(wall), 2);
+        }
+    }
+
+    #[test]
+    fn test_tremaux_route_avoids_marks() {
+        // The passages of the player and the one to the nearest frontier cell, on the top left, are marked
+        // twice: the player goes around to the frontier cell on the top right.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•"),
+            string_to_strings("#   |   #"),
+            string_to_strings("•-• • • •"),
+            string_to_strings("|       |"),
+            string_to_strings("•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (3, 3);
+        map.path = vec![(3, 3)];
+        for wall in [(3, 2), (2, 3), (3, 4), (1, 2)] {
+            map.marks.set(wall, 2);
+        }
+
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+        assert_eq!(
+            map.plan.as_ref().map(|plan: &Plan| plan.target),
+            Some((1, 7))
+        );
+    }
+
+    #[test]
+    fn test_wall_follower() {
+        // A ring of cells around a closed cell.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("• •-• •"),
+            string_to_strings("| | | |"),
+            string_to_strings("• •-• •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::East);
+        map.player_position = (1, 1);
+        map.path = vec![(1, 1)];
+        assert_eq!(
+            map.next_move_wall_follower(RelativeDirection::Right),
+            Some((RelativeDirection::Right, CardinalDirection::South))
+        );
+        // A move refused by the server was not made, choosing it again is no loop.
+        map.rollback_move(&ActionError::SolveChallengeFirst);
+        assert_eq!(
+            map.next_move_wall_follower(RelativeDirection::Right),
+            Some((RelativeDirection::Right, CardinalDirection::South))
+        );
+        assert!(!map.is_follower_looping);
+        map.follower_moves.clear();
+
+        let mut directions: Vec<CardinalDirection> = Vec::new();
+        while let Some((_, direction)) = map.next_move_wall_follower(RelativeDirection::Left) {
+            directions.push(direction);
+            map.accept_move();
+        }
+        // Back on its first cell, the follower would go around the island again.
+        assert_eq!(directions.len(), 8);
+        assert_eq!(directions[0], CardinalDirection::East);
+        assert_eq!(map.player_position, (1, 1));
+        assert!(map.is_follower_looping);
+        assert!(Strategy::LeftHand.next_move(&mut map).is_some());
+    }
+
+    #[test]
+    fn test_frontier_strategy() {
+        // A corridor whose both ends open to unknown cells, 2 moves away from the player.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("#         #"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.player_position, (1, 5));
+        assert_eq!(
+            map.next_move_frontier(),
+            Some((RelativeDirection::Left, CardinalDirection::West))
+        );
+
+        // The compass points East.
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.record_compass(90.0);
+        assert_eq!(map.goal_bearing, Some(90.0));
+        assert_eq!(
+            map.next_move_frontier(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+        assert_eq!(
+            map.plan.as_ref().map(Plan::cells),
+            Some(vec![(1, 5), (1, 7), (1, 9)])
+        );
+
+        // The plan is followed to its end.
+        map.accept_move();
+        map.goal_bearing = Some(270.0);
+        assert_eq!(
+            map.next_move_frontier(),
+            Some((RelativeDirection::Front, CardinalDirection::East))
+        );
+        assert_eq!(
+            map.plan.as_ref().map(Plan::cells),
+            Some(vec![(1, 7), (1, 9)])
+        );
+        map.accept_move();
+        assert_eq!(map.plan.as_ref().map(Plan::is_done), Some(true));
+    }
+
+    #[test]
+    fn test_sectors() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("#         #"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.sector_of((1, 1)), CardinalDirection::West);
+        assert_eq!(map.sector_of((-3, 7)), CardinalDirection::North);
+        map.sector_origin = Some((1, 9));
+        assert_eq!(map.sector_of((1, 7)), CardinalDirection::West);
+        map.sector_origin = None;
+        assert_eq!(
+            map.sector_costs(),
+            vec![(CardinalDirection::East, 2), (CardinalDirection::West, 2)]
+        );
+
+        // The sector given by the coordinator outweighs the compass.
+        map.record_compass(270.0);
+        map.sector = Some(CardinalDirection::East);
+        assert_eq!(
+            map.next_move_frontier(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+    }
+
+    #[test]
+    fn test_replan() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("#         #"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.record_compass(90.0);
+        map.next_move_frontier();
+        assert_eq!(
+            map.plan.as_ref().map(|plan: &Plan| plan.target),
+            Some((1, 9))
+        );
+
+        // A view far from the plan keeps it, a wall on its way outdates it.
+        let mut view: Vec<Vec<String>> = vec![string_to_strings("#######"); 7];
+        map.pending_move = None;
+        map.commit_move(&view);
+        assert!(map.plan.is_some());
+        view[3][4] = String::from("|");
+        map.commit_move(&view);
+        assert_eq!(map.plan, None);
+        assert_eq!(
+            map.next_move_frontier(),
+            Some((RelativeDirection::Left, CardinalDirection::West))
+        );
+
+        // So does a refused move.
+        map.rollback_move(&ActionError::CannotPassThroughOpponent);
+        assert_eq!(map.plan, None);
+    }
+
+    #[test]
+    fn test_rollback_move() {
+        // The wall on the East is unknown.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | | |"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | # |"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("| | | |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+
+        // The server refuses the move: nothing moved and the wall is known.
+        map.rollback_move(&ActionError::CannotPassThroughWall);
+        assert_eq!(map.player_position, (3, 3));
+        assert_eq!(map.current_cardinal_direction, CardinalDirection::North);
+        assert_eq!(map.visits.value((3, 5)), 0);
+        assert_eq!(map.cell(3, 4), "|");
+        assert_eq!(map.next_move_tremaux(), None);
+    }
+
+    #[test]
+    fn test_resume() {
+        // A corridor of 4 cells from West to East, the player in the second one.
+        let inside = |(column, row): (isize, isize)| -> bool {
+            return (0..4).contains(&column) && row == 0;
+        };
+        let wall = |cell: (isize, isize), direction: CardinalDirection| -> Option<bool> {
+            let neighbour: (isize, isize) = match direction {
+                CardinalDirection::North => (cell.0, cell.1 - 1),
+                CardinalDirection::East => (cell.0 + 1, cell.1),
+                CardinalDirection::South => (cell.0, cell.1 + 1),
+                CardinalDirection::West => (cell.0 - 1, cell.1),
+            };
+            if !inside(cell) && !inside(neighbour) {
+                return None;
+            }
+            return Some(!(inside(cell) && inside(neighbour)));
+        };
+        let item = |cell: (isize, isize)| -> Option<RadarItem> {
+            return inside(cell).then_some(RadarItem {
+                is_hint: false,
+                is_goal: false,
+                entity: None,
+            });
+        };
+        let first_view: String = RadarView::capture((1, 0), CardinalDirection::North, wall, item);
+        let moved_view: String = RadarView::capture((2, 0), CardinalDirection::East, wall, item);
+
+        // The connection was lost after the move East was sent, and the server made it.
+        let mut map: Map = Map::new(
+            &RadarView::new(first_view.clone(), CardinalDirection::North).grid,
+            CardinalDirection::North,
+        );
+        map.pending_move = Some(CardinalDirection::East);
+        assert_eq!(map.resume(&moved_view), CardinalDirection::East);
+        assert_eq!(map.player_position, (3, 5));
+        assert_eq!(map.contradictions, 0);
+
+        // The connection was lost before the server received the move.
+        let mut map: Map = Map::new(
+            &RadarView::new(first_view.clone(), CardinalDirection::North).grid,
+            CardinalDirection::North,
+        );
+        map.pending_move = Some(CardinalDirection::East);
+        assert_eq!(map.resume(&first_view), CardinalDirection::North);
+        assert_eq!(map.player_position, (3, 3));
+        assert_eq!(map.pending_move, None);
+        assert_eq!(map.contradictions, 0);
+    }
+
+    #[test]
+    fn test_monster_avoidance() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|  M  |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+
+        // Alone, the player does not attack the monster in front of it.
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.cell_priority(1, 3), 5);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+
+        // With an ally next to the monster, it attacks.
+        let mut grid_with_ally: Vec<Vec<String>> = grid.clone();
+        grid_with_ally[1][5] = String::from("A");
+        let mut map: Map = Map::new(&grid_with_ally, CardinalDirection::North);
+        assert_eq!(map.cell_priority(1, 3), 0);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Front, CardinalDirection::North))
+        );
+
+        // Unless the server needs a larger group.
+        map.monster_group_size = 3;
+        assert_eq!(map.cell_priority(1, 3), 5);
+
+        // With an ally in sight, the player converges next to the monster.
+        let mut grid_with_ally: Vec<Vec<String>> = grid.clone();
+        grid_with_ally[5][5] = String::from("A");
+        let map: Map = Map::new(&grid_with_ally, CardinalDirection::North);
+        assert_eq!(map.cell_priority(1, 5), 1);
+        assert_eq!(map.cell_priority(3, 5), 2);
+    }
+
+    #[test]
+    fn test_respawn() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (1, 1);
+        map.merge_radar_view(
+            &vec![string_to_strings("#######"); 7],
+            CardinalDirection::West,
+        );
+        assert_eq!(map.player_position, (1, -1));
+        assert_eq!(map.spawn_position, (3, 3));
+
+        map.respawn();
+        assert_eq!(map.player_position, (3, 3));
+        assert_eq!(map.current_cardinal_direction, CardinalDirection::North);
+    }
+
+    #[test]
+    fn test_restart() {
+        let saved: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•"),
+            string_to_strings("|   |     |"),
+            string_to_strings("• •-• •-• •"),
+            string_to_strings("|   |   | |"),
+            string_to_strings("•-• • •-• •"),
+            string_to_strings("|     |   |"),
+            string_to_strings("• •-•-• •-•"),
+            string_to_strings("|   |     |"),
+            string_to_strings("•-• • •-• •"),
+            string_to_strings("|     |   |"),
+            string_to_strings("•-•-•-•-•-•"),
+        ];
+        let view = |row: usize, column: usize| -> Vec<Vec<String>> {
+            return saved[row - 3..=row + 3]
+                .iter()
+                .map(|line: &Vec<String>| line[column - 3..=column + 3].to_vec())
+                .collect();
+        };
+
+        // The new game starts on the saved spawn.
+        let mut map: Map = Map::new(&saved, CardinalDirection::North);
+        map.player_position = (1, 1);
+        map.current_cardinal_direction = CardinalDirection::East;
+        assert!(map.restart(&Map::new(&view(5, 5), CardinalDirection::North)));
+        assert_eq!(map.spawn_position, (5, 5));
+        assert_eq!(map.player_position, (5, 5));
+        assert_eq!(map.current_cardinal_direction, CardinalDirection::North);
+
+        // The new game starts elsewhere on the saved map.
+        let mut map: Map = Map::new(&saved, CardinalDirection::North);
+        assert!(map.restart(&Map::new(&view(3, 7), CardinalDirection::North)));
+        assert_eq!(map.spawn_position, (3, 7));
+        assert_eq!(map.player_position, (3, 7));
+
+        // The new game is on another maze.
+        let mut map: Map = Map::new(&saved, CardinalDirection::North);
+        let other: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-• •"),
+            string_to_strings("|     |"),
+            string_to_strings("• •-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        assert!(!map.restart(&Map::new(&other, CardinalDirection::North)));
+        assert_eq!(map.spawn_position, (5, 5));
+    }
+
+    #[test]
+    fn test_hint_policy() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|H|   #"),
+            string_to_strings("• • •-•"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.hints, vec![(1, 1)]);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Left, CardinalDirection::West))
+        );
+
+        map.hint_policy = HintPolicy::Ignore;
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Front, CardinalDirection::North))
+        );
+
+        // A visited hint gives nothing more.
+        map.hint_policy = HintPolicy::Always;
+        map.visits.set((1, 1), 1);
+        assert!(map.unvisited_hints().is_empty());
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Front, CardinalDirection::North))
+        );
+        assert_eq!("always".parse(), Ok(HintPolicy::Always));
+        assert!("never".parse::<HintPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_hint_detour() {
+        // A corridor open to the East, the hint 5 moves West of the player.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•-•-•-•-•-•-•-•-•"),
+            string_to_strings("|H                    #"),
+            string_to_strings("•-•-•-•-•-•-•-•-•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.player_position, (1, 11));
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+
+        map.hint_policy = HintPolicy::Always;
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Left, CardinalDirection::West))
+        );
+    }
+
+    #[test]
+    fn test_seal_dead_ends() {
+        // A dead end branch of two cells North of the player, another one West, unknown cells East.
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|   | |"),
+            string_to_strings("•-• •-•"),
+            string_to_strings("|     #"),
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.player_position, (3, 3));
+        assert!(map.is_sealed((1, 1)));
+        assert!(map.is_sealed((1, 3)));
+        assert!(map.is_sealed((3, 1)));
+        assert!(!map.is_sealed((3, 3)));
+        assert!(!map.is_sealed((3, 5)));
+        assert_eq!(
+            map.nearest_frontier(),
+            Some(((3, 5), CardinalDirection::East))
+        );
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+
+        // A player at the end of a sealed branch finds its way out.
+        map.player_position = (1, 1);
+        assert_eq!(
+            map.nearest_frontier(),
+            Some(((3, 5), CardinalDirection::East))
+        );
+    }
+
+    #[test]
+    fn test_blocked_by_opponent() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Front, CardinalDirection::North))
+        );
+
+        // The server refuses the move: the player stays and plans around the opponent.
+        map.rollback_move(&ActionError::CannotPassThroughOpponent);
+        assert_eq!(map.player_position, (3, 3));
+        assert_eq!(map.current_cardinal_direction, CardinalDirection::North);
+        assert_eq!(map.visits.value((1, 3)), 0);
+        assert_eq!(map.blocked_attempts.value((1, 3)), 1);
+        assert_eq!(map.cell_priority(1, 3), 4);
+        assert_eq!(
+            map.next_move_tremaux(),
+            Some((RelativeDirection::Right, CardinalDirection::East))
+        );
+    }
+
+    #[test]
+    fn test_discovered_cells() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("##•-•-•"),
+            string_to_strings("##|   |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•-•"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        assert_eq!(map.discovered_cells(), 8);
+        assert_eq!(map.visits_at_player(), 0);
+
+        map.next_move_tremaux();
+        map.accept_move();
+        assert_eq!(map.visits_at_player(), 1);
     }
 }
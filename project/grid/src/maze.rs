@@ -0,0 +1,147 @@
+use shared::types::cardinal_direction::CardinalDirection;
+
+/// A cell of a maze: (column, row), the row growing towards the South.
+pub type Cell = (usize, usize);
+
+/// A whole maze, as known by a server: its walls, its exit and its hints.
+pub struct Maze {
+    pub columns: usize,
+    pub rows: usize,
+    /// The walls on the North side of each cell, then the South border: `rows + 1` rows of `columns` walls.
+    pub horizontal_walls: Vec<Vec<bool>>,
+    /// The walls on the West side of each cell, then the East border: `rows` rows of `columns + 1` walls.
+    pub vertical_walls: Vec<Vec<bool>>,
+    pub goal: Cell,
+    pub hints: Vec<Cell>,
+}
+
+impl Maze {
+    /// Creates a maze whose cells are all closed, the exit being in the bottom right cell.
+    /// The generators carve the passages into it.
+    pub fn new(columns: usize, rows: usize) -> Maze {
+        return Maze {
+            columns,
+            rows,
+            horizontal_walls: vec![vec![true; columns]; rows + 1],
+            vertical_walls: vec![vec![true; columns + 1]; rows],
+            goal: (columns - 1, rows - 1),
+            hints: Vec::new(),
+        };
+    }
+
+    /// Creates a maze without any wall but its border.
+    pub fn open(columns: usize, rows: usize) -> Maze {
+        let mut maze: Maze = Maze::new(columns, rows);
+        for row in 1..rows {
+            maze.horizontal_walls[row] = vec![false; columns];
+        }
+        for walls in maze.vertical_walls.iter_mut() {
+            walls[1..columns].fill(false);
+        }
+        return maze;
+    }
+
+    /// Tells if there is a wall on a side of a cell of the maze.
+    pub fn has_wall(&self, cell: Cell, direction: CardinalDirection) -> bool {
+        let (column, row) = cell;
+        return match direction {
+            CardinalDirection::North => self.horizontal_walls[row][column],
+            CardinalDirection::South => self.horizontal_walls[row + 1][column],
+            CardinalDirection::West => self.vertical_walls[row][column],
+            CardinalDirection::East => self.vertical_walls[row][column + 1],
+        };
+    }
+
+    /// Adds or removes the wall on a side of a cell of the maze.
+    pub fn set_wall(&mut self, cell: Cell, direction: CardinalDirection, is_wall: bool) {
+        let (column, row) = cell;
+        match direction {
+            CardinalDirection::North => self.horizontal_walls[row][column] = is_wall,
+            CardinalDirection::South => self.horizontal_walls[row + 1][column] = is_wall,
+            CardinalDirection::West => self.vertical_walls[row][column] = is_wall,
+            CardinalDirection::East => self.vertical_walls[row][column + 1] = is_wall,
+        }
+    }
+
+    /// Returns the cell next to `cell` in `direction`, or `None` if it is outside of the maze.
+    pub fn neighbour(&self, cell: Cell, direction: CardinalDirection) -> Option<Cell> {
+        let (column, row) = cell;
+        return match direction {
+            CardinalDirection::North if 0 < row => Some((column, row - 1)),
+            CardinalDirection::East if column + 1 < self.columns => Some((column + 1, row)),
+            CardinalDirection::South if row + 1 < self.rows => Some((column, row + 1)),
+            CardinalDirection::West if 0 < column => Some((column - 1, row)),
+            _ => None,
+        };
+    }
+
+    /// Returns the cell reached by moving from `cell` in `direction`, or `None` if a wall is in the way.
+    pub fn open_neighbour(&self, cell: Cell, direction: CardinalDirection) -> Option<Cell> {
+        if self.has_wall(cell, direction) {
+            return None;
+        }
+        return self.neighbour(cell, direction);
+    }
+
+    /// Returns the cells reachable from `cell` in one move, in the North, East, South, West order.
+    pub fn open_neighbours(&self, cell: Cell) -> Vec<Cell> {
+        return [
+            CardinalDirection::North,
+            CardinalDirection::East,
+            CardinalDirection::South,
+            CardinalDirection::West,
+        ]
+        .iter()
+        .filter_map(|&direction| self.open_neighbour(cell, direction))
+        .collect();
+    }
+
+    /// Tells if there is a wall on a side of a cell, as seen by the radar.
+    /// The border of the maze is a wall. The walls between two cells outside of the maze are undefined.
+    pub fn wall(&self, cell: (isize, isize), direction: CardinalDirection) -> Option<bool> {
+        let (column_offset, row_offset) = match direction {
+            CardinalDirection::North => (0, -1),
+            CardinalDirection::East => (1, 0),
+            CardinalDirection::South => (0, 1),
+            CardinalDirection::West => (-1, 0),
+        };
+        let neighbour: (isize, isize) = (cell.0 + column_offset, cell.1 + row_offset);
+
+        return match (self.contains(cell), self.contains(neighbour)) {
+            (false, false) => None,
+            (true, true) => Some(self.has_wall((cell.0 as usize, cell.1 as usize), direction)),
+            _ => Some(true),
+        };
+    }
+
+    /// Tells if a cell, whose coordinates may be negative, is inside the maze.
+    pub fn contains(&self, cell: (isize, isize)) -> bool {
+        return 0 <= cell.0
+            && 0 <= cell.1
+            && (cell.0 as usize) < self.columns
+            && (cell.1 as usize) < self.rows;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walls() {
+        let mut maze: Maze = Maze::open(3, 2);
+        assert!(maze.has_wall((0, 0), CardinalDirection::North));
+        assert!(maze.has_wall((2, 1), CardinalDirection::East));
+        assert!(!maze.has_wall((1, 0), CardinalDirection::South));
+        assert!(!maze.has_wall((1, 1), CardinalDirection::West));
+
+        maze.set_wall((1, 0), CardinalDirection::South, true);
+        assert!(maze.has_wall((1, 1), CardinalDirection::North));
+        assert_eq!(
+            maze.neighbour((1, 0), CardinalDirection::South),
+            Some((1, 1))
+        );
+        assert_eq!(maze.neighbour((1, 0), CardinalDirection::North), None);
+        assert!(Maze::new(3, 2).has_wall((1, 1), CardinalDirection::West));
+    }
+}
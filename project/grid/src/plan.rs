@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use shared::types::cardinal_direction::CardinalDirection;
+
+use crate::map::move_offsets;
+use crate::store::Position;
+
+/// The moves planned to reach a target cell, made one per turn.
+///
+/// The map drops its plan when the player leaves it, when a move is refused and when a radar view reveals
+/// a wall or an entity on its way, or the goal: the strategy then plans again with what it knows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// The cell the next move leaves from.
+    pub from: Position,
+    pub target: Position,
+    /// The cardinal moves left to make, the next one first.
+    pub moves: VecDeque<CardinalDirection>,
+}
+
+impl Plan {
+    /// Creates a plan from its moves, the target being where they lead.
+    pub fn new(from: Position, moves: Vec<CardinalDirection>) -> Plan {
+        let mut target: Position = from;
+        for &direction in &moves {
+            target = step(target, direction);
+        }
+        return Plan {
+            from,
+            target,
+            moves: VecDeque::from(moves),
+        };
+    }
+
+    pub fn next_move(&self) -> Option<CardinalDirection> {
+        return self.moves.front().copied();
+    }
+
+    /// Takes the next move off the plan, once the server has accepted it.
+    pub fn advance(&mut self) {
+        if let Some(direction) = self.moves.pop_front() {
+            self.from = step(self.from, direction);
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        return self.moves.is_empty();
+    }
+
+    /// Returns the cells of the plan, from the cell of the next move to the target, both included.
+    pub fn cells(&self) -> Vec<Position> {
+        let mut cells: Vec<Position> = Vec::with_capacity(self.moves.len() + 1);
+        cells.push(self.from);
+        for &direction in &self.moves {
+            cells.push(step(*cells.last().unwrap(), direction));
+        }
+        return cells;
+    }
+
+    /// Tells if the plan goes through a position: one of its cells or one of the walls between them.
+    pub fn crosses(&self, position: Position) -> bool {
+        let mut cell: Position = self.from;
+        if cell == position {
+            return true;
+        }
+        for &direction in &self.moves {
+            let (_, (wall_row_offset, wall_col_offset)) = move_offsets(direction);
+            let next: Position = step(cell, direction);
+            if position == (cell.0 + wall_row_offset, cell.1 + wall_col_offset) || position == next
+            {
+                return true;
+            }
+            cell = next;
+        }
+        return false;
+    }
+}
+
+/// Returns the cell next to a cell in a direction.
+fn step((row, column): Position, direction: CardinalDirection) -> Position {
+    let ((row_offset, col_offset), _) = move_offsets(direction);
+    return (row + row_offset, column + col_offset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let mut plan: Plan = Plan::new(
+            (1, 1),
+            vec![CardinalDirection::East, CardinalDirection::South],
+        );
+        assert_eq!(plan.target, (3, 3));
+        assert_eq!(plan.cells(), vec![(1, 1), (1, 3), (3, 3)]);
+        assert!(plan.crosses((1, 2)));
+        assert!(plan.crosses((2, 3)));
+        assert!(!plan.crosses((2, 1)));
+
+        plan.advance();
+        assert_eq!(plan.from, (1, 3));
+        assert_eq!(plan.next_move(), Some(CardinalDirection::South));
+        assert!(!plan.crosses((1, 2)));
+        plan.advance();
+        assert!(plan.is_done());
+        assert_eq!(plan.cells(), vec![(3, 3)]);
+    }
+}
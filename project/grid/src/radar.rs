@@ -1,12 +1,20 @@
 use shared::{
     log_debug, log_error,
     types::{
+        action::RelativeDirection,
         cardinal_direction::CardinalDirection,
         radar_item::{Entity, RadarItem},
     },
-    utils::decode_base64,
+    utils::{decode_base64, encode_base64},
 };
 
+/// The horizontal walls, the vertical walls and the cells of a view, in the frame of the player.
+type RadarMatrices = (
+    Vec<Vec<Option<bool>>>,
+    Vec<Vec<Option<bool>>>,
+    Vec<Vec<Option<RadarItem>>>,
+);
+
 pub struct RadarView {
     pub encoded_view: String,
     pub decoded_view: Vec<u8>,
@@ -45,7 +53,7 @@ impl RadarView {
                 match self.horizontal_walls[i][j] {
                     Some(true) => self.grid[2 * i][2 * j + 1] = String::from("-"),
                     Some(false) => self.grid[2 * i][2 * j + 1] = String::from(" "),
-                    None => (),
+                    None => self.grid[2 * i][2 * j + 1] = String::from("#"),
                 }
             }
         }
@@ -55,7 +63,7 @@ impl RadarView {
                 match self.vertical_walls[i][j] {
                     Some(true) => self.grid[2 * i + 1][2 * j] = String::from("|"),
                     Some(false) => self.grid[2 * i + 1][2 * j] = String::from(" "),
-                    None => (),
+                    None => self.grid[2 * i + 1][2 * j] = String::from("#"),
                 }
             }
         }
@@ -82,6 +90,8 @@ impl RadarView {
                         } => String::from("M"),
                         _ => String::from(" "),
                     };
+                } else {
+                    self.grid[wall_x][wall_y] = String::from("#");
                 }
             }
         }
@@ -101,7 +111,7 @@ impl RadarView {
         // •     •
         // |     |
         // •-•-•-•
-        if self.grid[2][3] == String::from("-") {
+        if self.grid[2][3] == "-" {
             self.grid[0][2] = String::from("#");
             self.grid[1][2] = String::from("#");
             self.grid[0][3] = String::from("#");
@@ -115,7 +125,7 @@ impl RadarView {
             // •     •
             // |     |
             // •-•-•-•
-            if self.grid[2][1] == String::from("-") {
+            if self.grid[2][1] == "-" {
                 self.grid[0][0] = String::from("#");
                 self.grid[0][1] = String::from("#");
                 self.grid[1][0] = String::from("#");
@@ -128,7 +138,7 @@ impl RadarView {
             // •     •
             // |     |
             // •-•-•-•
-            if self.grid[2][5] == String::from("-") {
+            if self.grid[2][5] == "-" {
                 self.grid[0][5] = String::from("#");
                 self.grid[0][6] = String::from("#");
                 self.grid[1][5] = String::from("#");
@@ -142,7 +152,7 @@ impl RadarView {
         // ##•   •
         // |     |
         // •-•-•-•
-        if self.grid[3][2] == String::from("|") {
+        if self.grid[3][2] == "|" {
             self.grid[2][0] = String::from("#");
             self.grid[2][1] = String::from("#");
             self.grid[3][0] = String::from("#");
@@ -156,7 +166,7 @@ impl RadarView {
             // ##•   •
             // |     |
             // •-•-•-•
-            if self.grid[1][2] == String::from("|") {
+            if self.grid[1][2] == "|" {
                 self.grid[0][0] = String::from("#");
                 self.grid[0][1] = String::from("#");
                 self.grid[1][0] = String::from("#");
@@ -169,7 +179,7 @@ impl RadarView {
             // ##•   •
             // ##|   |
             // ##•-•-•
-            if self.grid[5][2] == String::from("|") {
+            if self.grid[5][2] == "|" {
                 self.grid[6][0] = String::from("#");
                 self.grid[6][1] = String::from("#");
                 self.grid[5][0] = String::from("#");
@@ -183,7 +193,7 @@ impl RadarView {
         // • •-• •
         // | ### |
         // •-###-•
-        if self.grid[4][3] == String::from("-") {
+        if self.grid[4][3] == "-" {
             self.grid[5][2] = String::from("#");
             self.grid[5][3] = String::from("#");
             self.grid[5][4] = String::from("#");
@@ -197,7 +207,7 @@ impl RadarView {
             // •-•-• •
             // ##### |
             // #####-•
-            if self.grid[4][1] == String::from("-") {
+            if self.grid[4][1] == "-" {
                 self.grid[5][0] = String::from("#");
                 self.grid[5][1] = String::from("#");
                 self.grid[6][0] = String::from("#");
@@ -210,7 +220,7 @@ impl RadarView {
             // • •-•-•
             // | #####
             // •-#####
-            if self.grid[4][5] == String::from("-") {
+            if self.grid[4][5] == "-" {
                 self.grid[5][5] = String::from("#");
                 self.grid[5][6] = String::from("#");
                 self.grid[6][5] = String::from("#");
@@ -224,7 +234,7 @@ impl RadarView {
         // •   •##
         // |     |
         // •-•-•-•
-        if self.grid[3][4] == String::from("|") {
+        if self.grid[3][4] == "|" {
             self.grid[2][5] = String::from("#");
             self.grid[2][6] = String::from("#");
             self.grid[3][5] = String::from("#");
@@ -238,7 +248,7 @@ impl RadarView {
             // •   •##
             // |     |
             // •-•-•-•
-            if self.grid[1][4] == String::from("|") {
+            if self.grid[1][4] == "|" {
                 self.grid[0][5] = String::from("#");
                 self.grid[0][6] = String::from("#");
                 self.grid[1][5] = String::from("#");
@@ -251,7 +261,7 @@ impl RadarView {
             // •   •##
             // |   |##
             // •-•-•##
-            if self.grid[5][4] == String::from("|") {
+            if self.grid[5][4] == "|" {
                 self.grid[5][5] = String::from("#");
                 self.grid[5][6] = String::from("#");
                 self.grid[6][5] = String::from("#");
@@ -265,8 +275,8 @@ impl RadarView {
         // •     •
         // |     |
         // •-•-•-•
-        if self.grid[1][2] == String::from("|") && self.grid[2][1] == String::from("-")
-            || (self.grid[2][3] == String::from("-") && self.grid[3][2] == String::from("|"))
+        if self.grid[1][2] == "|" && self.grid[2][1] == "-"
+            || (self.grid[2][3] == "-" && self.grid[3][2] == "|")
         {
             self.grid[0][0] = String::from("#");
             self.grid[0][1] = String::from("#");
@@ -280,8 +290,8 @@ impl RadarView {
         // •     •
         // |     |
         // •-•-•-•
-        if self.grid[2][5] == String::from("-") && self.grid[1][4] == String::from("|")
-            || (self.grid[2][3] == String::from("-") && self.grid[3][4] == String::from("|"))
+        if self.grid[2][5] == "-" && self.grid[1][4] == "|"
+            || (self.grid[2][3] == "-" && self.grid[3][4] == "|")
         {
             self.grid[0][6] = String::from("#");
             self.grid[0][5] = String::from("#");
@@ -295,8 +305,8 @@ impl RadarView {
         // •-•   •
         // ##|   |
         // ##•-•-•
-        if self.grid[4][1] == String::from("-") && self.grid[5][2] == String::from("|")
-            || (self.grid[3][2] == String::from("|") && self.grid[4][3] == String::from("-"))
+        if self.grid[4][1] == "-" && self.grid[5][2] == "|"
+            || (self.grid[3][2] == "|" && self.grid[4][3] == "-")
         {
             self.grid[6][0] = String::from("#");
             self.grid[6][1] = String::from("#");
@@ -310,8 +320,8 @@ impl RadarView {
         // •   •-•
         // |   |##
         // •-•-•##
-        if self.grid[4][5] == String::from("-") && self.grid[5][4] == String::from("|")
-            || (self.grid[3][4] == String::from("|") && self.grid[4][3] == String::from("-"))
+        if self.grid[4][5] == "-" && self.grid[5][4] == "|"
+            || (self.grid[3][4] == "|" && self.grid[4][3] == "-")
         {
             self.grid[5][5] = String::from("#");
             self.grid[5][6] = String::from("#");
@@ -320,7 +330,7 @@ impl RadarView {
         }
     }
 
-    pub fn decode_view(&mut self) -> () {
+    pub fn decode_view(&mut self) {
         let decoded: Vec<u8> =
             decode_base64(&self.encoded_view).expect("Invalid Base64 data for RadarView");
         if decoded.len() != 11 {
@@ -367,7 +377,7 @@ impl RadarView {
         return bit_string;
     }
 
-    fn rotate_radar_view(&mut self) -> () {
+    fn rotate_radar_view(&mut self) {
         self.grid = match self.cardinal_direction {
             CardinalDirection::North => self.grid.clone(),
             CardinalDirection::East => RadarView::rotate_90_clockwise(&self.grid.clone()),
@@ -433,22 +443,22 @@ impl RadarView {
 
     pub fn print_horizontal_walls(&self) {
         log_debug!("Horizontal walls:");
-        let _ = RadarView::print_matrix(&self.horizontal_walls);
+        RadarView::print_matrix(&self.horizontal_walls);
     }
 
     pub fn print_vertical_walls(&self) {
         log_debug!("Vertical walls:");
-        let _ = RadarView::print_matrix(&self.vertical_walls);
+        RadarView::print_matrix(&self.vertical_walls);
     }
 
     pub fn print_cells_items(&self) {
         log_debug!("Cells items:");
-        let _ = RadarView::print_matrix(&self.radar_items);
+        RadarView::print_matrix(&self.radar_items);
     }
 
     pub fn print_walls(&self) {
         log_debug!("Walls:");
-        let _ = RadarView::print_matrix(&self.grid);
+        RadarView::print_matrix(&self.grid);
     }
 
     pub fn print_encoded_view(&self) {
@@ -516,47 +526,24 @@ impl RadarView {
     }
 
     fn get_radar_item_from_bits(bits: &str) -> Option<RadarItem> {
-        return match bits {
-            "0000" => Some(RadarItem {
-                is_hint: false,
-                is_goal: false,
-                entity: None,
-            }),
-            "0100" => Some(RadarItem {
-                is_hint: true,
-                is_goal: false,
-                entity: None,
-            }),
-            "1000" => Some(RadarItem {
-                is_hint: false,
-                is_goal: true,
-                entity: None,
-            }),
-            "1100" => Some(RadarItem {
-                is_hint: true,
-                is_goal: true,
-                entity: None,
-            }),
-            "0001" => Some(RadarItem {
-                is_hint: false,
-                is_goal: false,
-                entity: Some(Entity::Ally),
-            }),
-            "0010" => Some(RadarItem {
-                is_hint: false,
-                is_goal: false,
-                entity: Some(Entity::Enemy),
-            }),
-            "0011" => Some(RadarItem {
-                is_hint: false,
-                is_goal: false,
-                entity: Some(Entity::Monster),
-            }),
-            _ => None,
+        let value: u8 = match u8::from_str_radix(bits, 2) {
+            Ok(value) if value != 0b1111 => value,
+            _ => return None,
         };
+
+        return Some(RadarItem {
+            is_hint: value & 0b0100 != 0,
+            is_goal: value & 0b1000 != 0,
+            entity: match value & 0b0011 {
+                0b01 => Some(Entity::Ally),
+                0b10 => Some(Entity::Enemy),
+                0b11 => Some(Entity::Monster),
+                _ => None,
+            },
+        });
     }
 
-    fn rotate_90_clockwise(matrix: &Vec<Vec<String>>) -> Vec<Vec<String>> {
+    fn rotate_90_clockwise(matrix: &[Vec<String>]) -> Vec<Vec<String>> {
         let rows: usize = matrix.len();
         if rows == 0 {
             return vec![];
@@ -565,12 +552,12 @@ impl RadarView {
 
         let mut rotated: Vec<Vec<String>> = vec![vec![String::from("#"); rows]; cols];
 
-        for row in 0..rows {
-            for col in 0..cols {
+        for (row, matrix_row) in matrix.iter().enumerate() {
+            for (col, value) in matrix_row.iter().enumerate() {
                 let new_row: usize = col;
                 let new_col: usize = rows - 1 - row;
 
-                rotated[new_row][new_col] = match matrix[row][col].as_str() {
+                rotated[new_row][new_col] = match value.as_str() {
                     "-" => "|".to_owned(),
                     "|" => "-".to_owned(),
                     other => other.to_string(),
@@ -582,6 +569,259 @@ impl RadarView {
     }
 }
 
+/*=============================================================*\
+    ENCODING
+*\=============================================================*/
+
+impl RadarView {
+    /// Builds the encoded radar view of a player, as the server sends it.
+    /// The view is expressed in the frame of the player: what it faces is at the top.
+    /// Everything around the player is shown, see `capture_visible` to hide what is behind the walls.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The cell of the player: (column, row), the row growing towards the South.
+    /// * `heading` - The direction the player is facing.
+    /// * `wall` - Tells if there is a wall on a side of a cell, `None` if it is undefined.
+    /// * `item` - Returns the content of a cell, `None` if it is undefined.
+    ///
+    /// # Returns
+    ///
+    /// The radar view encoded in Base64.
+    pub fn capture<W, I>(
+        position: (isize, isize),
+        heading: CardinalDirection,
+        wall: W,
+        item: I,
+    ) -> String
+    where
+        W: Fn((isize, isize), CardinalDirection) -> Option<bool>,
+        I: Fn((isize, isize)) -> Option<RadarItem>,
+    {
+        let (horizontal_walls, vertical_walls, radar_items) =
+            RadarView::capture_matrices(position, heading, wall, item);
+        return RadarView::encode(&horizontal_walls, &vertical_walls, &radar_items);
+    }
+
+    /// Builds the encoded radar view of a player like `capture`, but hides what the player cannot see
+    /// from its cell, as the reference server does:
+    ///
+    /// * A side cell is seen when no wall stands between it and the player.
+    /// * A corner cell is seen through a seen side cell which is open towards it.
+    /// * A wall is seen when it borders a seen cell, except the walls between a seen corner cell and a
+    ///   hidden side cell.
+    ///
+    /// The hidden walls are undefined (`00`) and the hidden cells are undefined (`1111`).
+    pub fn capture_visible<W, I>(
+        position: (isize, isize),
+        heading: CardinalDirection,
+        wall: W,
+        item: I,
+    ) -> String
+    where
+        W: Fn((isize, isize), CardinalDirection) -> Option<bool>,
+        I: Fn((isize, isize)) -> Option<RadarItem>,
+    {
+        let (mut horizontal_walls, mut vertical_walls, mut radar_items) =
+            RadarView::capture_matrices(position, heading, wall, item);
+        RadarView::occlude(&mut horizontal_walls, &mut vertical_walls, &mut radar_items);
+        return RadarView::encode(&horizontal_walls, &vertical_walls, &radar_items);
+    }
+
+    /// Returns the walls and the cells around a player, in its frame.
+    fn capture_matrices<W, I>(
+        position: (isize, isize),
+        heading: CardinalDirection,
+        wall: W,
+        item: I,
+    ) -> RadarMatrices
+    where
+        W: Fn((isize, isize), CardinalDirection) -> Option<bool>,
+        I: Fn((isize, isize)) -> Option<RadarItem>,
+    {
+        let front: (isize, isize) = direction_offset(heading);
+        let right: (isize, isize) = direction_offset(heading.turn(RelativeDirection::Right));
+        let left_side: CardinalDirection = heading.turn(RelativeDirection::Left);
+
+        // The cell at (row, column) of the view, the player being at (1, 1).
+        let cell = |row: usize, column: usize| -> (isize, isize) {
+            let row_offset: isize = row as isize - 1;
+            let column_offset: isize = column as isize - 1;
+            return (
+                position.0 - row_offset * front.0 + column_offset * right.0,
+                position.1 - row_offset * front.1 + column_offset * right.1,
+            );
+        };
+
+        // Each horizontal wall is the top side of the cell below it.
+        let horizontal_walls: Vec<Vec<Option<bool>>> = (0..4)
+            .map(|i: usize| (0..3).map(|j: usize| wall(cell(i, j), heading)).collect())
+            .collect();
+        // Each vertical wall is the left side of the cell on its right.
+        let vertical_walls: Vec<Vec<Option<bool>>> = (0..3)
+            .map(|i: usize| (0..4).map(|j: usize| wall(cell(i, j), left_side)).collect())
+            .collect();
+        let radar_items: Vec<Vec<Option<RadarItem>>> = (0..3)
+            .map(|i: usize| (0..3).map(|j: usize| item(cell(i, j))).collect())
+            .collect();
+
+        return (horizontal_walls, vertical_walls, radar_items);
+    }
+
+    /// Makes undefined the walls and the cells of a view that the player, in the center, cannot see.
+    fn occlude(
+        horizontal_walls: &mut [Vec<Option<bool>>],
+        vertical_walls: &mut [Vec<Option<bool>>],
+        radar_items: &mut [Vec<Option<RadarItem>>],
+    ) {
+        // Tells if the wall between two cells of the view sharing a side is known to be open.
+        let is_open = |(row, column): (usize, usize), (other_row, other_column): (usize, usize)| {
+            let wall: Option<bool> = if row == other_row {
+                vertical_walls[row][column.max(other_column)]
+            } else {
+                horizontal_walls[row.max(other_row)][column]
+            };
+            return wall == Some(false);
+        };
+
+        let mut visible: [[bool; 3]; 3] = [[false; 3]; 3];
+        visible[1][1] = true;
+        for (row, column) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+            visible[row][column] = is_open((1, 1), (row, column));
+        }
+        for (row, column) in [(0, 0), (0, 2), (2, 0), (2, 2)] {
+            visible[row][column] = [(row, 1), (1, column)]
+                .iter()
+                .any(|&side: &(usize, usize)| {
+                    visible[side.0][side.1] && is_open(side, (row, column))
+                });
+        }
+
+        let is_corner = |(row, column): (usize, usize)| row != 1 && column != 1;
+        // A wall is seen from a seen cell next to it, unless a seen corner cell looks at a hidden cell.
+        let is_seen = |cell: Option<(usize, usize)>, other: Option<(usize, usize)>| {
+            return match cell {
+                Some((row, column)) if visible[row][column] => {
+                    !is_corner((row, column))
+                        || other.is_none_or(|(row, column)| visible[row][column])
+                }
+                _ => false,
+            };
+        };
+        let view_cell = |row: isize, column: isize| -> Option<(usize, usize)> {
+            if (0..3).contains(&row) && (0..3).contains(&column) {
+                return Some((row as usize, column as usize));
+            }
+            return None;
+        };
+
+        for (i, walls) in horizontal_walls.iter_mut().enumerate() {
+            for (j, wall) in walls.iter_mut().enumerate() {
+                let above: Option<(usize, usize)> = view_cell(i as isize - 1, j as isize);
+                let below: Option<(usize, usize)> = view_cell(i as isize, j as isize);
+                if !is_seen(above, below) && !is_seen(below, above) {
+                    *wall = None;
+                }
+            }
+        }
+        for (i, walls) in vertical_walls.iter_mut().enumerate() {
+            for (j, wall) in walls.iter_mut().enumerate() {
+                let left: Option<(usize, usize)> = view_cell(i as isize, j as isize - 1);
+                let right: Option<(usize, usize)> = view_cell(i as isize, j as isize);
+                if !is_seen(left, right) && !is_seen(right, left) {
+                    *wall = None;
+                }
+            }
+        }
+        for (i, items) in radar_items.iter_mut().enumerate() {
+            for (j, item) in items.iter_mut().enumerate() {
+                if !visible[i][j] {
+                    *item = None;
+                }
+            }
+        }
+    }
+
+    /// Encodes the walls and the cells of a radar view, the reverse of the decoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `horizontal_walls` - 4 rows of 3 walls.
+    /// * `vertical_walls` - 3 rows of 4 walls.
+    /// * `radar_items` - 3 rows of 3 cells.
+    pub fn encode(
+        horizontal_walls: &[Vec<Option<bool>>],
+        vertical_walls: &[Vec<Option<bool>>],
+        radar_items: &[Vec<Option<RadarItem>>],
+    ) -> String {
+        let mut bytes: Vec<u8> = Vec::with_capacity(11);
+        bytes.extend(RadarView::encode_walls(horizontal_walls));
+        bytes.extend(RadarView::encode_walls(vertical_walls));
+
+        // 9 cells of 4 bits, the first one in the most significant bits, then 4 bits of padding.
+        let raw_40_bits: u64 =
+            radar_items
+                .iter()
+                .flatten()
+                .fold(0u64, |bits: u64, radar_item: &Option<RadarItem>| {
+                    (bits << 4) | RadarView::get_bits_from_radar_item(radar_item) as u64
+                })
+                << 4;
+        bytes.extend(&raw_40_bits.to_be_bytes()[3..8]);
+
+        return encode_base64(&bytes);
+    }
+
+    /// Packs 12 walls of 2 bits, the first one in the most significant bits, in little endian.
+    fn encode_walls(walls: &[Vec<Option<bool>>]) -> [u8; 3] {
+        let raw_bits: u32 = walls
+            .iter()
+            .flatten()
+            .fold(0u32, |bits: u32, wall: &Option<bool>| {
+                let wall_bits: u32 = match wall {
+                    None => 0b00,
+                    Some(false) => 0b01,
+                    Some(true) => 0b10,
+                };
+                (bits << 2) | wall_bits
+            });
+
+        return [
+            raw_bits as u8,
+            (raw_bits >> 8) as u8,
+            (raw_bits >> 16) as u8,
+        ];
+    }
+
+    fn get_bits_from_radar_item(radar_item: &Option<RadarItem>) -> u8 {
+        let radar_item: &RadarItem = match radar_item {
+            Some(radar_item) => radar_item,
+            None => return 0b1111,
+        };
+
+        let goal_bits: u8 = if radar_item.is_goal { 0b1000 } else { 0 };
+        let hint_bits: u8 = if radar_item.is_hint { 0b0100 } else { 0 };
+        let entity_bits: u8 = match radar_item.entity {
+            None => 0b00,
+            Some(Entity::Ally) => 0b01,
+            Some(Entity::Enemy) => 0b10,
+            Some(Entity::Monster) => 0b11,
+        };
+
+        return goal_bits | hint_bits | entity_bits;
+    }
+}
+
+/// Returns the offset of the neighbour cell in a direction: (column, row), the row growing towards the South.
+fn direction_offset(direction: CardinalDirection) -> (isize, isize) {
+    return match direction {
+        CardinalDirection::North => (0, -1),
+        CardinalDirection::East => (1, 0),
+        CardinalDirection::South => (0, 1),
+        CardinalDirection::West => (-1, 0),
+    };
+}
+
 /*=============================================================*\
     TESTS
 *\=============================================================*/
@@ -589,6 +829,7 @@ impl RadarView {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::maze::Maze;
     use shared::utils::{print_string_matrix, string_to_strings};
     use std::vec;
 
@@ -738,6 +979,136 @@ mod tests {
         assert_eq!(vertical_extracted_walls, expected_vertical_walls);
     }
 
+    #[test]
+    fn test_encode() {
+        for encoded_view in ["ieysGjGO8papd/a", "zAeaMsua//8aaaa", "kevQAjIvaaapapa"] {
+            let radar_view: RadarView =
+                RadarView::new(String::from(encoded_view), CardinalDirection::North);
+            assert_eq!(
+                RadarView::encode(
+                    &radar_view.horizontal_walls,
+                    &radar_view.vertical_walls,
+                    &radar_view.radar_items
+                ),
+                encoded_view
+            );
+        }
+    }
+
+    #[test]
+    fn test_capture() {
+        // A 2x2 open room, the exit in the bottom right cell and a monster in the top right one.
+        let inside =
+            |(column, row): (isize, isize)| (0..2).contains(&column) && (0..2).contains(&row);
+        let wall = |cell: (isize, isize), direction: CardinalDirection| {
+            let (column_offset, row_offset) = direction_offset(direction);
+            let neighbour: (isize, isize) = (cell.0 + column_offset, cell.1 + row_offset);
+            if !inside(cell) && !inside(neighbour) {
+                return None;
+            }
+            return Some(!(inside(cell) && inside(neighbour)));
+        };
+        let item = |cell: (isize, isize)| {
+            if !inside(cell) {
+                return None;
+            }
+            return Some(RadarItem {
+                is_hint: false,
+                is_goal: cell == (1, 1),
+                entity: if cell == (1, 0) {
+                    Some(Entity::Monster)
+                } else {
+                    None
+                },
+            });
+        };
+
+        // From the top left cell, facing North then East.
+        let radar_view: RadarView = RadarView::new(
+            RadarView::capture((0, 0), CardinalDirection::North, wall, item),
+            CardinalDirection::North,
+        );
+        let expected: Vec<Vec<String>> = vec![
+            string_to_strings("#######"),
+            string_to_strings("#######"),
+            string_to_strings("##•-•-•"),
+            string_to_strings("##|  M|"),
+            string_to_strings("##• • •"),
+            string_to_strings("##|  G|"),
+            string_to_strings("##•-•-•"),
+        ];
+        assert_eq!(radar_view.grid, expected);
+
+        let radar_view: RadarView = RadarView::new(
+            RadarView::capture((0, 0), CardinalDirection::East, wall, item),
+            CardinalDirection::East,
+        );
+        assert_eq!(radar_view.grid, expected);
+    }
+
+    #[test]
+    fn test_capture_visible() {
+        // A closed 5x5 maze, the player in the center facing North, and the passages of the reference
+        // view `ieysGjGO8papd/a`: the front cell and its top side, the left cell and the cell below it.
+        let mut closed: Maze = Maze::new(5, 5);
+        closed.set_wall((2, 1), CardinalDirection::North, false);
+        closed.set_wall((2, 2), CardinalDirection::North, false);
+        closed.set_wall((2, 2), CardinalDirection::West, false);
+        closed.set_wall((1, 3), CardinalDirection::North, false);
+
+        // Open 5x5 mazes with the walls around the other views sent by the reference server.
+        let mut open: Maze = Maze::open(5, 5);
+        open.set_wall((2, 2), CardinalDirection::North, true);
+        open.set_wall((3, 2), CardinalDirection::North, true);
+        open.set_wall((2, 2), CardinalDirection::West, true);
+        open.set_wall((1, 3), CardinalDirection::West, true);
+        open.set_wall((2, 3), CardinalDirection::East, true);
+        open.set_wall((1, 3), CardinalDirection::South, true);
+
+        let mut walled: Maze = Maze::open(5, 5);
+        for ((column, row), direction) in [
+            ((2, 1), CardinalDirection::North),
+            ((3, 1), CardinalDirection::North),
+            ((1, 1), CardinalDirection::West),
+            ((1, 1), CardinalDirection::South),
+            ((1, 2), CardinalDirection::West),
+            ((2, 2), CardinalDirection::East),
+            ((1, 3), CardinalDirection::East),
+            ((2, 3), CardinalDirection::East),
+            ((1, 3), CardinalDirection::South),
+            ((2, 3), CardinalDirection::South),
+        ] {
+            walled.set_wall((column, row), direction, true);
+        }
+
+        for (maze, expected) in [
+            (closed, "ieysGjGO8papd/a"),
+            (open, "zAeaMsua//8aaaa"),
+            (walled, "kevQAjIvaaapapa"),
+        ] {
+            let item = |cell: (isize, isize)| {
+                return Some(RadarItem {
+                    is_hint: false,
+                    is_goal: false,
+                    entity: None,
+                })
+                .filter(|_| maze.contains(cell));
+            };
+            let wall =
+                |cell: (isize, isize), direction: CardinalDirection| maze.wall(cell, direction);
+
+            assert_eq!(
+                RadarView::capture_visible((2, 2), CardinalDirection::North, wall, item),
+                expected
+            );
+            // Without occlusion, the walls and the cells hidden behind the walls are sent too.
+            assert_ne!(
+                RadarView::capture((2, 2), CardinalDirection::North, wall, item),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_rotate_90_clockwise() {
         let radar_view: RadarView = RadarView {
@@ -0,0 +1,263 @@
+use std::io::{self, Write};
+
+use shared::types::cardinal_direction::CardinalDirection;
+
+use crate::map::Map;
+use crate::plan::Plan;
+
+/// Moves the cursor to the top left corner of the terminal.
+const CURSOR_HOME: &str = "\x1b[H";
+/// Clears the terminal from the cursor to the end of the screen.
+const CLEAR_TO_END: &str = "\x1b[J";
+/// Clears the terminal from the cursor to the end of the line, removing what is left of a wider frame.
+const CLEAR_TO_END_OF_LINE: &str = "\x1b[K";
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+
+/// The background colors of the visited cells, from one visit to many. They come from the 256 colors palette.
+const HEATMAP_COLORS: [u8; 6] = [22, 28, 34, 136, 166, 160];
+
+/// The characters drawing a corner, indexed by its walls: 1 = North, 2 = East, 4 = South and 8 = West.
+const CORNERS: [char; 16] = [
+    ' ', '╵', '╶', '└', '╷', '│', '┌', '├', '╴', '┘', '─', '┴', '┐', '┤', '┬', '┼',
+];
+
+/// Draws a `Map` in a terminal with box-drawing walls.
+///
+/// Each cell is 3 characters wide. With colors, the visited cells are shaded by their number of visits,
+/// and the markers are colored: goal in green, hints in yellow, allies in blue, opponents in red and
+/// monsters in magenta. The cells of the plan of the player are drawn with `·` and the unknown regions
+/// with `░`.
+pub struct Renderer {
+    pub colors: bool,
+}
+
+impl Renderer {
+    pub fn new(colors: bool) -> Renderer {
+        return Renderer { colors };
+    }
+
+    /// Returns the drawing of the map, one line per row of the grid.
+    pub fn render(&self, map: &Map) -> String {
+        let ((top, left), (bottom, right)) = match map.grid.bounds() {
+            Some(bounds) => bounds,
+            None => return String::from("\n"),
+        };
+        let mut lines: Vec<String> = Vec::with_capacity((bottom - top + 1) as usize);
+        for row in top..=bottom {
+            let mut line: String = String::new();
+            for column in left..=right {
+                line.push_str(&self.render_position(map, row, column));
+            }
+            if self.colors {
+                line.push_str(CLEAR_TO_END_OF_LINE);
+            }
+            lines.push(line);
+        }
+        return lines.join("\n") + "\n";
+    }
+
+    /// Draws a position of the grid: a corner, a wall or a cell depending on the parity of its indexes.
+    fn render_position(&self, map: &Map, row: isize, column: isize) -> String {
+        let content: &str = map.cell(row, column);
+        return match (row.rem_euclid(2), column.rem_euclid(2)) {
+            (0, 0) => self.render_corner(map, row, column),
+            (0, _) => match content {
+                "-" => String::from("───"),
+                "#" => self.paint(DIM, "░░░"),
+                _ => String::from("   "),
+            },
+            (_, 0) => match content {
+                "|" => String::from("│"),
+                "#" => self.paint(DIM, "░"),
+                _ => String::from(" "),
+            },
+            _ => self.render_cell(map, row, column),
+        };
+    }
+
+    fn render_corner(&self, map: &Map, row: isize, column: isize) -> String {
+        let around: [(&str, &str); 4] = [
+            (map.cell(row - 1, column), "|"),
+            (map.cell(row, column + 1), "-"),
+            (map.cell(row + 1, column), "|"),
+            (map.cell(row, column - 1), "-"),
+        ];
+
+        let mut walls: usize = 0;
+        for (bit, (content, wall)) in around.iter().enumerate() {
+            if content == wall {
+                walls |= 1 << bit;
+            }
+        }
+
+        let is_unknown: bool = around.iter().all(|(content, _)| *content == "#");
+        if walls == 0 && is_unknown {
+            return self.paint(DIM, "░");
+        }
+        return CORNERS[walls].to_string();
+    }
+
+    fn render_cell(&self, map: &Map, row: isize, column: isize) -> String {
+        if (row, column) == map.player_position {
+            let arrow: &str = match map.current_cardinal_direction {
+                CardinalDirection::North => " ↑ ",
+                CardinalDirection::East => " → ",
+                CardinalDirection::South => " ↓ ",
+                CardinalDirection::West => " ← ",
+            };
+            return self.paint("\x1b[1;97;44m", arrow);
+        }
+
+        let content: &str = map.entity(row, column).unwrap_or(map.cell(row, column));
+        let marker: Option<(&str, &str)> = match content {
+            "#" => return self.paint(DIM, "░░░"),
+            "G" => Some(("\x1b[1;92m", " G ")),
+            "H" => Some(("\x1b[1;93m", " H ")),
+            "A" => Some(("\x1b[1;94m", " A ")),
+            "E" => Some(("\x1b[1;91m", " E ")),
+            "M" => Some(("\x1b[1;95m", " M ")),
+            _ => None,
+        };
+        if let Some((style, text)) = marker {
+            return self.paint(style, text);
+        }
+        if map
+            .plan
+            .as_ref()
+            .is_some_and(|plan: &Plan| plan.crosses((row, column)))
+        {
+            return self.paint("\x1b[1;96m", " · ");
+        }
+
+        let visits: u32 = map.visits.value((row, column));
+        if visits == 0 {
+            return String::from("   ");
+        }
+        let color: u8 = HEATMAP_COLORS[(visits as usize - 1).min(HEATMAP_COLORS.len() - 1)];
+        if !self.colors {
+            return format!(" {} ", visits.min(9));
+        }
+        return self.paint(&format!("\x1b[48;5;{}m", color), "   ");
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if !self.colors {
+            return String::from(text);
+        }
+        return format!("{}{}{}", style, text, RESET);
+    }
+}
+
+/// Draws the maps of a team as one frame, each one under its title, in the order of the players.
+///
+/// The maps do not share an origin, so each one keeps its own panel. The players update their panel after
+/// their turns and the whole frame is drawn again over the previous one, so that they never draw over each
+/// other.
+pub struct TeamView {
+    pub renderer: Renderer,
+    /// The title and the drawing of the map of each player, once it has played.
+    panels: Vec<Option<(String, String)>>,
+}
+
+impl TeamView {
+    pub fn new(renderer: Renderer, players: usize) -> TeamView {
+        return TeamView {
+            renderer,
+            panels: vec![None; players],
+        };
+    }
+
+    /// Replaces the panel of a player with its map, then draws the frame over the previous one instead
+    /// of scrolling the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the player in the team.
+    /// * `title` - The line printed above the map, e.g. the name of the player.
+    /// * `map` - The map to draw.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be written.
+    pub fn update(&mut self, index: usize, title: &str, map: &Map) -> io::Result<()> {
+        self.panels[index] = Some((String::from(title), self.renderer.render(map)));
+
+        let frame: String = format!("{}{}{}", CURSOR_HOME, self.frame(), CLEAR_TO_END);
+        let mut stdout: io::StdoutLock<'_> = io::stdout().lock();
+        stdout.write_all(frame.as_bytes())?;
+        return stdout.flush();
+    }
+
+    /// Returns the panels of the players which have played, one under the other.
+    pub fn frame(&self) -> String {
+        return self
+            .panels
+            .iter()
+            .flatten()
+            .map(|(title, drawing): &(String, String)| {
+                format!("{}{}\n{}", title, CLEAR_TO_END_OF_LINE, drawing)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::utils::string_to_strings;
+
+    #[test]
+    fn test_render() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•##"),
+            string_to_strings("|G  |##"),
+            string_to_strings("• •-•##"),
+            string_to_strings("|   |##"),
+            string_to_strings("•-•-•##"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (3, 3);
+        map.current_cardinal_direction = CardinalDirection::East;
+        map.visits.set((3, 1), 2);
+
+        let expected: String = [
+            "┌───────┐░░░░",
+            "│ G     │░░░░",
+            "│   ╶───┤░░░░",
+            "│ 2   → │░░░░",
+            "└───────┘░░░░",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(Renderer::new(false).render(&map), expected);
+
+        map.plan = Some(Plan::new((3, 3), vec![CardinalDirection::West]));
+        assert!(Renderer::new(false).render(&map).contains("│ ·   → │░░░░"));
+    }
+
+    #[test]
+    fn test_team_view() {
+        let map: Map = Map::new(
+            &[
+                string_to_strings("•-•"),
+                string_to_strings("| |"),
+                string_to_strings("•-•"),
+            ],
+            CardinalDirection::North,
+        );
+        let mut team_view: TeamView = TeamView::new(Renderer::new(false), 3);
+        team_view.panels[2] = Some((String::from("P3"), team_view.renderer.render(&map)));
+        team_view.panels[0] = Some((String::from("P1"), team_view.renderer.render(&map)));
+
+        // The panels follow the order of the players, whatever the order of their turns.
+        let panel: String = ["┌───┐", "│ ↑ │", "└───┘", ""].join("\n");
+        assert_eq!(
+            team_view.frame(),
+            format!(
+                "P1{}\n{}P3{}\n{}",
+                CLEAR_TO_END_OF_LINE, panel, CLEAR_TO_END_OF_LINE, panel
+            )
+        );
+    }
+}
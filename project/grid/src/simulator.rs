@@ -0,0 +1,350 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use shared::types::cardinal_direction::CardinalDirection;
+use shared::types::error::ActionError;
+use shared::types::radar_item::{Entity, RadarItem};
+
+use crate::map::Map;
+use crate::maze::{Cell, Maze};
+use crate::radar::RadarView;
+use crate::strategy::Strategy;
+
+/// A player of a simulated game: where it really is and what it knows.
+pub struct SimulatedPlayer {
+    pub position: Cell,
+    pub heading: CardinalDirection,
+    pub map: Map,
+    pub moves: u32,
+    pub wall_collisions: u32,
+    /// The number of moves after which the player reached the exit, if it did.
+    pub exited_after: Option<u32>,
+    /// Set when the strategy found no move to make.
+    pub is_stuck: bool,
+}
+
+/// How a player ended a simulated game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerOutcome {
+    /// The moves sent, the ones refused by a wall included.
+    pub moves: u32,
+    pub wall_collisions: u32,
+    pub found_exit: bool,
+}
+
+/// Plays a game of a team on a maze without any server: the players take turns, receive the radar views
+/// the server would send and choose their moves with a strategy, as the worker does.
+pub struct Simulation<'a> {
+    pub maze: &'a Maze,
+    pub players: Vec<SimulatedPlayer>,
+    pub strategy: Strategy,
+}
+
+impl<'a> Simulation<'a> {
+    /// Places the players on their start cells, facing North, with the radar view seen from there.
+    ///
+    /// # Arguments
+    ///
+    /// * `maze` - The maze to play.
+    /// * `starts` - The start cell of each player.
+    /// * `strategy` - How the players choose their moves.
+    /// * `seed` - The seed of the random choices of the players.
+    pub fn new(maze: &'a Maze, starts: &[Cell], strategy: Strategy, seed: u64) -> Simulation<'a> {
+        let mut simulation: Simulation<'a> = Simulation {
+            maze,
+            players: Vec::with_capacity(starts.len()),
+            strategy,
+        };
+        for &position in starts {
+            simulation.players.push(SimulatedPlayer {
+                position,
+                heading: CardinalDirection::North,
+                map: Map::new(&[vec![String::new()]], CardinalDirection::North),
+                moves: 0,
+                wall_collisions: 0,
+                exited_after: None,
+                is_stuck: false,
+            });
+        }
+        let mut rng: StdRng = StdRng::seed_from_u64(seed);
+        for index in 0..simulation.players.len() {
+            let view: RadarView = RadarView::new(simulation.view(index), CardinalDirection::North);
+            let mut map: Map = Map::new(&view.grid, CardinalDirection::North);
+            map.rng = StdRng::seed_from_u64(rng.gen());
+            simulation.players[index].map = map;
+        }
+        return simulation;
+    }
+
+    /// Returns the encoded radar view of a player, as the server sends it.
+    /// The other players still in the maze are its allies.
+    pub fn view(&self, index: usize) -> String {
+        let player: &SimulatedPlayer = &self.players[index];
+        let item = |cell: (isize, isize)| -> Option<RadarItem> {
+            if !self.maze.contains(cell) {
+                return None;
+            }
+            let cell: Cell = (cell.0 as usize, cell.1 as usize);
+            let has_ally: bool = self.players.iter().enumerate().any(|(other, player)| {
+                other != index && player.exited_after.is_none() && player.position == cell
+            });
+            return Some(RadarItem {
+                is_hint: self.maze.hints.contains(&cell),
+                is_goal: cell == self.maze.goal,
+                entity: if has_ally { Some(Entity::Ally) } else { None },
+            });
+        };
+
+        return RadarView::capture_visible(
+            (player.position.0 as isize, player.position.1 as isize),
+            player.heading,
+            |cell: (isize, isize), direction: CardinalDirection| self.maze.wall(cell, direction),
+            item,
+        );
+    }
+
+    /// Plays one turn of a player: its strategy chooses a move, which is applied to the maze then to its
+    /// map, with the new radar view when the move succeeds.
+    pub fn play_turn(&mut self, index: usize) {
+        let strategy: Strategy = self.strategy;
+        let player: &mut SimulatedPlayer = &mut self.players[index];
+        let direction: CardinalDirection = match strategy.next_move(&mut player.map) {
+            Some((_, direction)) => direction,
+            None => {
+                player.is_stuck = true;
+                return;
+            }
+        };
+        player.moves += 1;
+
+        let position: Cell = match self.maze.open_neighbour(player.position, direction) {
+            Some(position) => position,
+            None => {
+                player.wall_collisions += 1;
+                player
+                    .map
+                    .rollback_move(&ActionError::CannotPassThroughWall);
+                return;
+            }
+        };
+        player.position = position;
+        player.heading = direction;
+        if position == self.maze.goal {
+            player.exited_after = Some(player.moves);
+        }
+
+        let view: RadarView = RadarView::new(self.view(index), direction);
+        self.players[index].map.commit_move(&view.grid);
+    }
+
+    /// Plays turns until every player has reached the exit, is stuck or has made `max_moves` moves.
+    pub fn run(&mut self, max_moves: u32) -> Vec<PlayerOutcome> {
+        loop {
+            let mut has_played: bool = false;
+            for index in 0..self.players.len() {
+                let player: &SimulatedPlayer = &self.players[index];
+                if player.exited_after.is_some() || player.is_stuck || max_moves <= player.moves {
+                    continue;
+                }
+                self.play_turn(index);
+                has_played = true;
+            }
+            if !has_played {
+                break;
+            }
+        }
+
+        return self
+            .players
+            .iter()
+            .map(|player: &SimulatedPlayer| PlayerOutcome {
+                moves: player.exited_after.unwrap_or(player.moves),
+                wall_collisions: player.wall_collisions,
+                found_exit: player.exited_after.is_some(),
+            })
+            .collect();
+    }
+}
+
+/// Plays a whole game and returns how each player ended it.
+///
+/// # Arguments
+///
+/// * `maze` - The maze to play.
+/// * `starts` - The start cell of each player.
+/// * `strategy` - How the players choose their moves.
+/// * `max_moves` - The moves after which a player gives up.
+/// * `seed` - The seed of the random choices of the players.
+pub fn simulate(
+    maze: &Maze,
+    starts: &[Cell],
+    strategy: Strategy,
+    max_moves: u32,
+    seed: u64,
+) -> Vec<PlayerOutcome> {
+    return Simulation::new(maze, starts, strategy, seed).run(max_moves);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate, Generator, GeneratorOptions};
+
+    #[test]
+    fn test_corridor() {
+        // A corridor going East, the exit at its end.
+        let mut maze: Maze = Maze::new(4, 1);
+        for column in 0..3 {
+            maze.set_wall((column, 0), CardinalDirection::East, false);
+        }
+
+        let mut simulation: Simulation = Simulation::new(&maze, &[(0, 0)], Strategy::Tremaux, 0);
+        assert_eq!(
+            simulation.players[0].map.grid.to_rows()[3],
+            vec!["#", "#", "|", " ", " ", " ", " "]
+        );
+        let outcomes: Vec<PlayerOutcome> = simulation.run(100);
+        assert_eq!(
+            outcomes,
+            vec![PlayerOutcome {
+                moves: 3,
+                wall_collisions: 0,
+                found_exit: true,
+            }]
+        );
+        assert_eq!(simulation.players[0].position, (3, 0));
+    }
+
+    #[test]
+    fn test_tremaux_finds_the_exit() {
+        for generator in [Generator::RecursiveBacktracking, Generator::Prims] {
+            let options: GeneratorOptions = GeneratorOptions {
+                generator,
+                seed: 5,
+                braid: 0.2,
+            };
+            let maze: Maze = generate(12, 12, &options);
+            let outcomes: Vec<PlayerOutcome> = simulate(
+                &maze,
+                &[(0, 0), (11, 0), (0, 11)],
+                Strategy::Tremaux,
+                10_000,
+                0,
+            );
+            assert!(outcomes
+                .iter()
+                .all(|outcome: &PlayerOutcome| outcome.found_exit));
+            assert_eq!(
+                outcomes,
+                simulate(
+                    &maze,
+                    &[(0, 0), (11, 0), (0, 11)],
+                    Strategy::Tremaux,
+                    10_000,
+                    0
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_tremaux_terminates_on_loops() {
+        for generator in [Generator::RecursiveBacktracking, Generator::Ellers] {
+            let options: GeneratorOptions = GeneratorOptions {
+                generator,
+                seed: 7,
+                braid: 1.0,
+            };
+            let mut maze: Maze = generate(10, 10, &options);
+            // Without an exit to find, the player explores the whole maze.
+            maze.goal = (maze.columns, maze.rows);
+            let passages: u32 = (1..maze.rows)
+                .map(|row: usize| {
+                    maze.horizontal_walls[row]
+                        .iter()
+                        .filter(|&&wall| !wall)
+                        .count()
+                })
+                .chain((0..maze.rows).map(|row: usize| {
+                    maze.vertical_walls[row][1..maze.columns]
+                        .iter()
+                        .filter(|&&wall| !wall)
+                        .count()
+                }))
+                .sum::<usize>() as u32;
+
+            let mut simulation: Simulation =
+                Simulation::new(&maze, &[(4, 4)], Strategy::Tremaux, 0);
+            simulation.run(100_000);
+            let player: &SimulatedPlayer = &simulation.players[0];
+            assert!(player.is_stuck);
+            assert!(player.moves <= 2 * passages);
+            assert_eq!(player.map.discovered_cells(), 100);
+        }
+    }
+
+    #[test]
+    fn test_wall_followers_find_the_exit() {
+        // The followers go around the islands of the braided maze, then switch to Trémaux.
+        for braid in [0.0, 1.0] {
+            let options: GeneratorOptions = GeneratorOptions {
+                generator: Generator::RecursiveBacktracking,
+                seed: 3,
+                braid,
+            };
+            let maze: Maze = generate(12, 12, &options);
+            for strategy in [Strategy::LeftHand, Strategy::RightHand] {
+                let outcomes: Vec<PlayerOutcome> =
+                    simulate(&maze, &[(0, 0), (11, 0), (0, 11)], strategy, 10_000, 0);
+                assert!(outcomes
+                    .iter()
+                    .all(|outcome: &PlayerOutcome| outcome.found_exit));
+            }
+        }
+        assert_eq!("right-hand".parse(), Ok(Strategy::RightHand));
+    }
+
+    #[test]
+    fn test_frontier_finds_the_exit() {
+        let options: GeneratorOptions = GeneratorOptions {
+            generator: Generator::Prims,
+            seed: 11,
+            braid: 0.5,
+        };
+        let maze: Maze = generate(12, 12, &options);
+        let outcomes: Vec<PlayerOutcome> = simulate(
+            &maze,
+            &[(0, 0), (11, 0), (0, 11)],
+            Strategy::Frontier,
+            10_000,
+            0,
+        );
+        assert!(outcomes
+            .iter()
+            .all(|outcome: &PlayerOutcome| outcome.found_exit));
+        assert_eq!("frontier".parse(), Ok(Strategy::Frontier));
+    }
+
+    #[test]
+    fn test_max_moves() {
+        let maze: Maze = Maze::open(20, 20);
+        let outcomes: Vec<PlayerOutcome> = simulate(&maze, &[(0, 0)], Strategy::Random, 5, 0);
+        assert_eq!(outcomes[0].moves, 5);
+        assert!(!outcomes[0].found_exit);
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let options: GeneratorOptions = GeneratorOptions {
+            generator: Generator::Prims,
+            seed: 13,
+            braid: 0.5,
+        };
+        let maze: Maze = generate(12, 12, &options);
+        let starts: [Cell; 3] = [(0, 0), (11, 0), (0, 11)];
+        let outcomes: Vec<PlayerOutcome> = simulate(&maze, &starts, Strategy::Random, 10_000, 4);
+        assert_eq!(
+            outcomes,
+            simulate(&maze, &starts, Strategy::Random, 10_000, 4)
+        );
+    }
+}
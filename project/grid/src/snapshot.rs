@@ -0,0 +1,347 @@
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use shared::types::cardinal_direction::CardinalDirection;
+use shared::utils::string_to_strings;
+
+use crate::map::{is_cell, Map};
+use crate::maze::Maze;
+use crate::store::{Position, SparseGrid};
+
+/// The version of the snapshot format written by this module. Files of another version are refused.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// The saved state of a map. The rows of the grid are stored as strings to keep the files readable.
+/// The positions are those of the map, the first row of the grid starting at `origin`.
+#[derive(Serialize, Deserialize)]
+struct MapSnapshot {
+    version: u32,
+    origin: Position,
+    grid: Vec<String>,
+    visits: Vec<Vec<u32>>,
+    blocked_attempts: Vec<Vec<u32>>,
+    /// The Trémaux marks of the passages, so that a loaded map is not explored again.
+    marks: Vec<Vec<u8>>,
+    player_position: (isize, isize),
+    spawn_position: (isize, isize),
+    heading: CardinalDirection,
+    path: Vec<(isize, isize)>,
+    /// The position of the exit in the grid, if it has been seen.
+    goal: Option<(isize, isize)>,
+}
+
+/// The saved walls, exit and hints of a maze, drawn like the grid of a map.
+#[derive(Serialize, Deserialize)]
+struct MazeSnapshot {
+    version: u32,
+    grid: Vec<String>,
+}
+
+/// Only reads the version, to refuse a file of another version with a clear error.
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+}
+
+/// Saves a map to a file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn save_map(map: &Map, path: &str) -> io::Result<()> {
+    return fs::write(path, map_to_json(map)?);
+}
+
+/// Loads a map saved by `save_map`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid snapshot of this version.
+pub fn load_map(path: &str) -> io::Result<Map> {
+    return map_from_json(&fs::read_to_string(path)?);
+}
+
+/// Saves a maze to a file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn save_maze(maze: &Maze, path: &str) -> io::Result<()> {
+    return fs::write(path, maze_to_json(maze)?);
+}
+
+/// Loads a maze saved by `save_maze`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid snapshot of this version.
+pub fn load_maze(path: &str) -> io::Result<Maze> {
+    return maze_from_json(&fs::read_to_string(path)?);
+}
+
+pub fn map_to_json(map: &Map) -> io::Result<String> {
+    let (origin, bottom_right) = map
+        .grid
+        .bounds()
+        .ok_or_else(|| invalid_data("The map is empty"))?;
+    let snapshot: MapSnapshot = MapSnapshot {
+        version: SNAPSHOT_VERSION,
+        origin,
+        grid: map
+            .grid
+            .to_rows()
+            .iter()
+            .map(|row: &Vec<String>| row.concat())
+            .collect(),
+        visits: map.visits.rows_in(origin, bottom_right),
+        blocked_attempts: map.blocked_attempts.rows_in(origin, bottom_right),
+        marks: map.marks.rows_in(origin, bottom_right),
+        player_position: map.player_position,
+        spawn_position: map.spawn_position,
+        heading: map.current_cardinal_direction,
+        path: map.path.clone(),
+        goal: map.known_goal(),
+    };
+    return Ok(serde_json::to_string_pretty(&snapshot)?);
+}
+
+/// Rebuilds a map from its snapshot. The move pending when it was saved, if any, is dropped.
+///
+/// # Errors
+///
+/// Returns an error if the JSON is not a valid snapshot of this version.
+pub fn map_from_json(json: &str) -> io::Result<Map> {
+    check_version(json)?;
+    let snapshot: MapSnapshot = serde_json::from_str(json)?;
+
+    let mut grid: Vec<Vec<String>> = snapshot
+        .grid
+        .iter()
+        .map(|row: &String| string_to_strings(row))
+        .collect();
+    let (rows, columns) = check_dimensions(&grid)?;
+    if !has_dimensions(&snapshot.visits, rows, columns)
+        || !has_dimensions(&snapshot.blocked_attempts, rows, columns)
+        || !has_dimensions(&snapshot.marks, rows, columns)
+    {
+        return Err(invalid_data("The counters do not match the grid"));
+    }
+    let (top, left) = snapshot.origin;
+    let is_grid_cell = |(row, column): Position| -> bool {
+        return top <= row
+            && left <= column
+            && row < top + rows as isize
+            && column < left + columns as isize
+            && is_cell((row, column));
+    };
+    if !is_grid_cell(snapshot.player_position)
+        || !is_grid_cell(snapshot.spawn_position)
+        || !snapshot.path.iter().all(|&position| is_grid_cell(position))
+    {
+        return Err(invalid_data("A position is not a cell of the grid"));
+    }
+    if let Some((row, column)) = snapshot.goal.filter(|&goal| is_grid_cell(goal)) {
+        grid[(row - top) as usize][(column - left) as usize] = String::from("G");
+    }
+
+    let mut map: Map = Map::new_at(&grid, snapshot.origin, snapshot.heading);
+    map.visits = SparseGrid::from_rows(&snapshot.visits, snapshot.origin, |_| 0);
+    map.blocked_attempts =
+        SparseGrid::from_rows(&snapshot.blocked_attempts, snapshot.origin, |_| 0);
+    map.marks = SparseGrid::from_rows(&snapshot.marks, snapshot.origin, |_| 0);
+    map.player_position = snapshot.player_position;
+    map.spawn_position = snapshot.spawn_position;
+    map.path = snapshot.path;
+    // The seals depend on the position of the player and on the visited hints.
+    map.seal_dead_ends();
+    return Ok(map);
+}
+
+pub fn maze_to_json(maze: &Maze) -> io::Result<String> {
+    let mut grid: Vec<Vec<char>> = vec![vec![' '; 2 * maze.columns + 1]; 2 * maze.rows + 1];
+    for (row, walls) in maze.horizontal_walls.iter().enumerate() {
+        for (column, &is_wall) in walls.iter().enumerate() {
+            grid[2 * row][2 * column + 1] = if is_wall { '-' } else { ' ' };
+        }
+    }
+    for (row, walls) in maze.vertical_walls.iter().enumerate() {
+        for (column, &is_wall) in walls.iter().enumerate() {
+            grid[2 * row + 1][2 * column] = if is_wall { '|' } else { ' ' };
+        }
+    }
+    for row in (0..grid.len()).step_by(2) {
+        for column in (0..grid[row].len()).step_by(2) {
+            grid[row][column] = '•';
+        }
+    }
+    for &(column, row) in &maze.hints {
+        grid[2 * row + 1][2 * column + 1] = 'H';
+    }
+    grid[2 * maze.goal.1 + 1][2 * maze.goal.0 + 1] = 'G';
+
+    let snapshot: MazeSnapshot = MazeSnapshot {
+        version: SNAPSHOT_VERSION,
+        grid: grid
+            .iter()
+            .map(|row: &Vec<char>| row.iter().collect())
+            .collect(),
+    };
+    return Ok(serde_json::to_string_pretty(&snapshot)?);
+}
+
+/// Rebuilds a maze from its snapshot.
+///
+/// # Errors
+///
+/// Returns an error if the JSON is not a valid snapshot of this version or if the maze has no exit.
+pub fn maze_from_json(json: &str) -> io::Result<Maze> {
+    check_version(json)?;
+    let snapshot: MazeSnapshot = serde_json::from_str(json)?;
+    let grid: Vec<Vec<String>> = snapshot
+        .grid
+        .iter()
+        .map(|row: &String| string_to_strings(row))
+        .collect();
+    let (grid_rows, grid_columns) = check_dimensions(&grid)?;
+
+    let mut maze: Maze = Maze::new(grid_columns / 2, grid_rows / 2);
+    let mut goal: Option<(usize, usize)> = None;
+    for (grid_row, row) in grid.iter().enumerate() {
+        for (grid_column, content) in row.iter().enumerate() {
+            let (column, row) = (grid_column / 2, grid_row / 2);
+            match (grid_row % 2, grid_column % 2) {
+                (0, 1) => maze.horizontal_walls[row][column] = content == "-",
+                (1, 0) => maze.vertical_walls[row][column] = content == "|",
+                (1, 1) if content == "G" => goal = Some((column, row)),
+                (1, 1) if content == "H" => maze.hints.push((column, row)),
+                _ => {}
+            }
+        }
+    }
+    maze.goal = goal.ok_or_else(|| invalid_data("The maze has no exit"))?;
+
+    return Ok(maze);
+}
+
+fn check_version(json: &str) -> io::Result<()> {
+    let versioned: Versioned = serde_json::from_str(json)?;
+    if versioned.version != SNAPSHOT_VERSION {
+        return Err(invalid_data(&format!(
+            "Unsupported snapshot version {}, expected {}",
+            versioned.version, SNAPSHOT_VERSION
+        )));
+    }
+    return Ok(());
+}
+
+/// Checks that the rows of a grid have the same odd length and that their number is odd too.
+/// Returns the number of rows and columns.
+fn check_dimensions(grid: &[Vec<String>]) -> io::Result<(usize, usize)> {
+    let rows: usize = grid.len();
+    let columns: usize = grid.first().map_or(0, |row: &Vec<String>| row.len());
+    if rows < 3 || columns < 3 || rows.is_multiple_of(2) || columns.is_multiple_of(2) {
+        return Err(invalid_data(
+            "The grid must have an odd number of rows and columns",
+        ));
+    }
+    if grid.iter().any(|row: &Vec<String>| row.len() != columns) {
+        return Err(invalid_data("The rows of the grid have different lengths"));
+    }
+    return Ok((rows, columns));
+}
+
+/// Tells if a layer of counters has the given number of rows and columns.
+fn has_dimensions<T>(layer: &[Vec<T>], rows: usize, columns: usize) -> bool {
+    return layer.len() == rows && layer.iter().all(|row: &Vec<T>| row.len() == columns);
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_round_trip() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•-•-•"),
+            string_to_strings("|G    |"),
+            string_to_strings("• •-• •"),
+            string_to_strings("|     |"),
+            string_to_strings("• • • •"),
+            string_to_strings("|     |"),
+            string_to_strings("•-•-•##"),
+        ];
+        let mut map: Map = Map::new(&grid, CardinalDirection::North);
+        map.player_position = (3, 1);
+        map.current_cardinal_direction = CardinalDirection::West;
+        map.path.push((3, 1));
+        map.visits.set((3, 1), 1);
+        map.blocked_attempts.set((1, 1), 2);
+        map.marks.set((3, 2), 1);
+        map.pending_move = Some(CardinalDirection::North);
+
+        let loaded: Map = map_from_json(&map_to_json(&map).unwrap()).unwrap();
+        assert_eq!(loaded.grid.to_rows(), map.grid.to_rows());
+        assert_eq!(loaded.visits.value((3, 1)), 1);
+        assert_eq!(loaded.blocked_attempts.value((1, 1)), 2);
+        assert_eq!(loaded.marks.value((3, 2)), 1);
+        assert_eq!(loaded.player_position, (3, 1));
+        assert_eq!(loaded.spawn_position, (3, 3));
+        assert_eq!(loaded.current_cardinal_direction, CardinalDirection::West);
+        assert_eq!(loaded.path, vec![(3, 3), (3, 1)]);
+        assert_eq!(loaded.known_goal(), Some((1, 1)));
+        assert_eq!(loaded.pending_move, None);
+    }
+
+    #[test]
+    fn test_refused_snapshots() {
+        let grid: Vec<Vec<String>> = vec![
+            string_to_strings("•-•"),
+            string_to_strings("| |"),
+            string_to_strings("•-•"),
+        ];
+        let json: String = map_to_json(&Map::new(&grid, CardinalDirection::North)).unwrap();
+
+        let future: String = json.replace("\"version\": 3", "\"version\": 4");
+        let error: io::Error = map_from_json(&future).err().unwrap();
+        assert!(error.to_string().contains("Unsupported snapshot version 4"));
+
+        let outside: String = json.replace(
+            "\"player_position\": [\n    1,\n    1\n  ]",
+            "\"player_position\": [\n    9,\n    1\n  ]",
+        );
+        assert_ne!(outside, json);
+        assert!(map_from_json(&outside).is_err());
+    }
+
+    #[test]
+    fn test_maze_round_trip() {
+        let mut maze: Maze = Maze::open(3, 2);
+        maze.set_wall((1, 0), CardinalDirection::South, true);
+        maze.hints.push((0, 1));
+        maze.goal = (2, 0);
+
+        let json: String = maze_to_json(&maze).unwrap();
+        assert!(json.contains("\"|    G|\""));
+        let loaded: Maze = maze_from_json(&json).unwrap();
+        assert_eq!(loaded.horizontal_walls, maze.horizontal_walls);
+        assert_eq!(loaded.vertical_walls, maze.vertical_walls);
+        assert_eq!(loaded.hints, vec![(0, 1)]);
+        assert_eq!(loaded.goal, (2, 0));
+    }
+
+    #[test]
+    fn test_game_snapshot() {
+        // Saved by Player 1 at the end of a game on `server --maze 8,8 --seed 3`.
+        let json: &str = include_str!("../snapshots/maze_8x8_seed_3.json");
+        let mut map: Map = map_from_json(json).unwrap();
+        assert_eq!(map_to_json(&map).unwrap(), json);
+        assert_eq!(map.known_goal(), Some((17, 13)));
+        assert_eq!(map.spawn_position, (3, 9));
+        assert!(map.next_move_tremaux().is_some());
+    }
+}
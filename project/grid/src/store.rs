@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+/// The side of the square chunks of a `SparseGrid`.
+const CHUNK_SIZE: isize = 16;
+
+/// A position of a `SparseGrid`: its row then its column, both signed.
+pub type Position = (isize, isize);
+
+/// A grid without bounds, indexed by signed positions which never change when it grows.
+///
+/// The positions are stored by square chunks, created on the first write inside them: growing is a
+/// hash map insertion, nothing is copied. A position never written holds the value given by `fill`.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    chunks: HashMap<Position, Vec<T>>,
+    fill: fn(Position) -> T,
+    /// The top left and bottom right positions, included, of the covered area.
+    bounds: Option<(Position, Position)>,
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Creates an empty grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill` - The value of a position never written.
+    pub fn new(fill: fn(Position) -> T) -> SparseGrid<T> {
+        return SparseGrid {
+            chunks: HashMap::new(),
+            fill,
+            bounds: None,
+        };
+    }
+
+    /// Builds a grid from dense rows, the first one starting at `origin`.
+    pub fn from_rows(rows: &[Vec<T>], origin: Position, fill: fn(Position) -> T) -> SparseGrid<T> {
+        let mut grid: SparseGrid<T> = SparseGrid::new(fill);
+        for (row, values) in rows.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                grid.set(
+                    (origin.0 + row as isize, origin.1 + column as isize),
+                    value.clone(),
+                );
+            }
+        }
+        return grid;
+    }
+
+    /// Returns the value of a position, `None` if it was never written.
+    pub fn get(&self, (row, column): Position) -> Option<&T> {
+        return self
+            .chunks
+            .get(&chunk_of((row, column)))
+            .map(|chunk: &Vec<T>| &chunk[index_in_chunk((row, column))]);
+    }
+
+    /// Returns the value of a position, the fill value if it was never written.
+    pub fn value(&self, position: Position) -> T {
+        return self
+            .get(position)
+            .cloned()
+            .unwrap_or_else(|| (self.fill)(position));
+    }
+
+    /// Returns the value of a position to change it, creating its chunk if needed. The position is
+    /// covered from then on.
+    pub fn get_mut(&mut self, position: Position) -> &mut T {
+        self.cover(position, position);
+        let fill: fn(Position) -> T = self.fill;
+        let chunk: &mut Vec<T> = self.chunks.entry(chunk_of(position)).or_insert_with_key(
+            |&(chunk_row, chunk_column): &Position| {
+                (0..CHUNK_SIZE * CHUNK_SIZE)
+                    .map(|index: isize| {
+                        fill((
+                            chunk_row * CHUNK_SIZE + index / CHUNK_SIZE,
+                            chunk_column * CHUNK_SIZE + index % CHUNK_SIZE,
+                        ))
+                    })
+                    .collect()
+            },
+        );
+        return &mut chunk[index_in_chunk(position)];
+    }
+
+    pub fn set(&mut self, position: Position, value: T) {
+        *self.get_mut(position) = value;
+    }
+
+    /// Extends the covered area to a rectangle, given by its top left and bottom right positions.
+    pub fn cover(&mut self, top_left: Position, bottom_right: Position) {
+        self.bounds = Some(match self.bounds {
+            None => (top_left, bottom_right),
+            Some((old_top_left, old_bottom_right)) => (
+                (
+                    old_top_left.0.min(top_left.0),
+                    old_top_left.1.min(top_left.1),
+                ),
+                (
+                    old_bottom_right.0.max(bottom_right.0),
+                    old_bottom_right.1.max(bottom_right.1),
+                ),
+            ),
+        });
+    }
+
+    /// Returns the top left and bottom right positions, included, of the smallest rectangle containing
+    /// every covered position. `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<(Position, Position)> {
+        return self.bounds;
+    }
+
+    /// Tells if a position is in the covered rectangle.
+    pub fn contains(&self, (row, column): Position) -> bool {
+        return match self.bounds {
+            None => false,
+            Some(((top, left), (bottom, right))) => {
+                top <= row && row <= bottom && left <= column && column <= right
+            }
+        };
+    }
+
+    /// Returns the positions of the covered rectangle, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let ((top, left), (bottom, right)) = self.bounds.unwrap_or(((0, 0), (-1, -1)));
+        return (top..=bottom)
+            .flat_map(move |row: isize| (left..=right).map(move |column| (row, column)));
+    }
+
+    /// Copies the covered rectangle into dense rows, the first one starting at the top left position.
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        return match self.bounds {
+            None => Vec::new(),
+            Some((top_left, bottom_right)) => self.rows_in(top_left, bottom_right),
+        };
+    }
+
+    /// Copies a rectangle, given by its top left and bottom right positions, into dense rows.
+    pub fn rows_in(&self, (top, left): Position, (bottom, right): Position) -> Vec<Vec<T>> {
+        return (top..=bottom)
+            .map(|row: isize| {
+                (left..=right)
+                    .map(|column: isize| self.value((row, column)))
+                    .collect()
+            })
+            .collect();
+    }
+}
+
+fn chunk_of((row, column): Position) -> Position {
+    return (row.div_euclid(CHUNK_SIZE), column.div_euclid(CHUNK_SIZE));
+}
+
+fn index_in_chunk((row, column): Position) -> usize {
+    return (row.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + column.rem_euclid(CHUNK_SIZE)) as usize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid: SparseGrid<u32> = SparseGrid::new(|_| 0);
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.to_rows().is_empty());
+
+        grid.set((-1, 2), 5);
+        *grid.get_mut((40, -17)) += 3;
+        assert_eq!(grid.get((-1, 2)), Some(&5));
+        assert_eq!(grid.get((40, -17)), Some(&3));
+        // Written chunks hold the fill value around the written positions.
+        assert_eq!(grid.get((-5, 7)), Some(&0));
+        assert_eq!(grid.get((1000, 1000)), None);
+        assert_eq!(grid.value((1000, 1000)), 0);
+        assert_eq!(grid.bounds(), Some(((-1, -17), (40, 2))));
+        assert!(grid.contains((0, 0)));
+        assert!(!grid.contains((41, 0)));
+    }
+
+    #[test]
+    fn test_rows() {
+        let rows: Vec<Vec<String>> = vec![
+            vec![String::from("a"), String::from("b")],
+            vec![String::from("c"), String::from("d")],
+        ];
+        let mut grid: SparseGrid<String> =
+            SparseGrid::from_rows(&rows, (-2, 3), |_| String::from("#"));
+        assert_eq!(grid.to_rows(), rows);
+        assert_eq!(grid.get((-1, 4)), Some(&String::from("d")));
+
+        grid.cover((-2, 3), (-2, 5));
+        assert_eq!(
+            grid.to_rows(),
+            vec![
+                vec![String::from("a"), String::from("b"), String::from("#")],
+                vec![String::from("c"), String::from("d"), String::from("#")],
+            ]
+        );
+        assert_eq!(grid.positions().count(), 6);
+    }
+}
@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use shared::types::action::RelativeDirection;
+use shared::types::cardinal_direction::CardinalDirection;
+
+use crate::map::Map;
+
+/// The ways a player chooses its next move on its map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    /// Marks the passages it goes through and never takes one a third time, see `Map::next_move_tremaux`.
+    Tremaux,
+    /// Goes to a random reachable cell, preferring to go forward, see `Map::next_move_random`.
+    /// The simulator seeds it from the seed of the game, the worker does not: two runs give different games.
+    Random,
+    /// Keeps its left hand on the walls, then switches to Trémaux once it goes around an island, see
+    /// `Map::next_move_wall_follower`.
+    LeftHand,
+    /// Keeps its right hand on the walls, then switches to Trémaux once it goes around an island.
+    RightHand,
+    /// Plans routes to the frontier cells revealing the most, towards the compass, see
+    /// `Map::next_move_frontier`.
+    Frontier,
+}
+
+impl Strategy {
+    /// Returns the name of the strategy on the command line.
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Strategy::Tremaux => "tremaux",
+            Strategy::Random => "random",
+            Strategy::LeftHand => "left-hand",
+            Strategy::RightHand => "right-hand",
+            Strategy::Frontier => "frontier",
+        };
+    }
+
+    /// Chooses the next move of the player and makes it the pending move of the map.
+    ///
+    /// # Returns
+    ///
+    /// The move relative to the heading of the player and its cardinal direction, or `None` if the player
+    /// cannot move.
+    pub fn next_move(&self, map: &mut Map) -> Option<(RelativeDirection, CardinalDirection)> {
+        return match self {
+            Strategy::Tremaux => map.next_move_tremaux(),
+            Strategy::Random => map.next_move_random(),
+            Strategy::LeftHand => map
+                .next_move_wall_follower(RelativeDirection::Left)
+                .or_else(|| map.next_move_tremaux()),
+            Strategy::RightHand => map
+                .next_move_wall_follower(RelativeDirection::Right)
+                .or_else(|| map.next_move_tremaux()),
+            Strategy::Frontier => map.next_move_frontier(),
+        };
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return match value {
+            "tremaux" => Ok(Strategy::Tremaux),
+            "random" => Ok(Strategy::Random),
+            "left-hand" => Ok(Strategy::LeftHand),
+            "right-hand" => Ok(Strategy::RightHand),
+            "frontier" => Ok(Strategy::Frontier),
+            _ => Err(format!("Unknown strategy: {}", value)),
+        };
+    }
+}
@@ -0,0 +1,163 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::types::challenge::Challenge;
+
+use crate::config::ChallengeConfig;
+
+/// A challenge sent to a player and not solved yet.
+/// While it is running, the player receives no radar view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunningChallenge {
+    pub modulo: u64,
+}
+
+impl RunningChallenge {
+    /// Returns the challenge sent to the player, to send it again when the player resumes its session.
+    pub fn challenge(&self) -> Challenge {
+        return Challenge::SecretSumModulo(self.modulo);
+    }
+}
+
+/// Decides when secrets and challenges are distributed and checks the answers.
+pub struct ChallengeEngine {
+    config: ChallengeConfig,
+}
+
+impl ChallengeEngine {
+    pub fn new(config: ChallengeConfig) -> ChallengeEngine {
+        ChallengeEngine { config }
+    }
+
+    /// Tells if the acting player must receive a new secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `team_actions` - The number of actions performed by the team, including the current one.
+    pub fn is_secret_due(&self, team_actions: u32) -> bool {
+        return self.config.secret_period != 0
+            && team_actions.is_multiple_of(self.config.secret_period);
+    }
+
+    /// Tells if the radar view of the acting player must be replaced by a challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_moves` - The number of moves performed by the player, including the current one.
+    pub fn is_challenge_due(&self, player_moves: u32) -> bool {
+        return self.config.challenge_period != 0
+            && player_moves.is_multiple_of(self.config.challenge_period);
+    }
+
+    pub fn new_secret(&self, rng: &mut StdRng) -> u64 {
+        return rng.random::<u64>();
+    }
+
+    /// Creates a new `SecretSumModulo` challenge and the state to keep until it is solved.
+    pub fn new_challenge(&self, rng: &mut StdRng) -> (Challenge, RunningChallenge) {
+        let modulo: u64 = rng.random_range(2..self.config.max_modulo);
+        return (
+            Challenge::SecretSumModulo(modulo),
+            RunningChallenge { modulo },
+        );
+    }
+
+    /// Checks the answer of a `SecretSumModulo` challenge against the latest secrets of the team.
+    ///
+    /// The expected value is computed when the answer is received, so an answer computed before
+    /// a teammate received a new secret is stale and is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - The running challenge.
+    /// * `team_secrets` - The latest secret of each team member (`0` if it never received one).
+    /// * `answer` - The answer sent by the player.
+    pub fn is_answer_valid(
+        challenge: &RunningChallenge,
+        team_secrets: &[u64],
+        answer: &str,
+    ) -> bool {
+        let expected: u64 = ChallengeEngine::secret_sum_modulo(team_secrets, challenge.modulo);
+        return answer.trim().parse::<u64>() == Ok(expected);
+    }
+
+    /// Computes the sum of the secrets modulo `modulo` without overflowing.
+    pub fn secret_sum_modulo(secrets: &[u64], modulo: u64) -> u64 {
+        let sum: u128 = secrets
+            .iter()
+            .fold(0u128, |accumulator: u128, &secret: &u64| {
+                accumulator + secret as u128
+            });
+        return (sum % modulo as u128) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn new_engine(secret_period: u32, challenge_period: u32) -> ChallengeEngine {
+        ChallengeEngine::new(ChallengeConfig {
+            secret_period,
+            challenge_period,
+            max_modulo: 100,
+        })
+    }
+
+    #[test]
+    fn test_periods() {
+        let engine: ChallengeEngine = new_engine(2, 3);
+        assert!(!engine.is_secret_due(1));
+        assert!(engine.is_secret_due(2));
+        assert!(!engine.is_challenge_due(2));
+        assert!(engine.is_challenge_due(3));
+        assert!(engine.is_challenge_due(6));
+
+        let disabled: ChallengeEngine = new_engine(0, 0);
+        assert!(!disabled.is_secret_due(4));
+        assert!(!disabled.is_challenge_due(4));
+    }
+
+    #[test]
+    fn test_secret_sum_modulo() {
+        // Example of the README: Player 2 never received a secret.
+        assert_eq!(ChallengeEngine::secret_sum_modulo(&[11, 0, 32], 10), 3);
+        assert_eq!(
+            ChallengeEngine::secret_sum_modulo(&[u64::MAX, u64::MAX, 2], 7),
+            ((u64::MAX as u128 * 2 + 2) % 7) as u64
+        );
+    }
+
+    #[test]
+    fn test_is_answer_valid() {
+        let challenge: RunningChallenge = RunningChallenge { modulo: 10 };
+        let secrets: [u64; 3] = [11, 0, 32];
+        assert!(ChallengeEngine::is_answer_valid(&challenge, &secrets, "3"));
+        assert!(!ChallengeEngine::is_answer_valid(&challenge, &secrets, "4"));
+        assert!(!ChallengeEngine::is_answer_valid(
+            &challenge, &secrets, "three"
+        ));
+
+        // A teammate received a new secret: the previous answer is stale.
+        let updated_secrets: [u64; 3] = [11, 5, 32];
+        assert!(!ChallengeEngine::is_answer_valid(
+            &challenge,
+            &updated_secrets,
+            "3"
+        ));
+    }
+
+    #[test]
+    fn test_new_challenge() {
+        let engine: ChallengeEngine = new_engine(1, 1);
+        let mut rng: StdRng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let (challenge, running) = engine.new_challenge(&mut rng);
+            assert!((2..100).contains(&running.modulo));
+            match challenge {
+                Challenge::SecretSumModulo(modulo) => assert_eq!(modulo, running.modulo),
+                Challenge::SOS => panic!("Expected a SecretSumModulo challenge."),
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::io;
+
+use grid::generator::Generator;
+use grid::map::MONSTER_GROUP_SIZE;
+
+/// Options of the mini server, read from the command line.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub port: u16,
+    pub team_size: u8,
+    pub seed: Option<u64>,
+    /// Size of the maze in cells: (columns, rows).
+    pub maze_size: (u32, u32),
+    pub maze: MazeConfig,
+    /// In team fight mode, only teammates can share a cell.
+    pub team_fight: bool,
+    pub challenge: ChallengeConfig,
+    pub hint: HintConfig,
+    pub monster: MonsterConfig,
+}
+
+/// How the walls of the maze are generated.
+#[derive(Debug, Clone)]
+pub struct MazeConfig {
+    /// The algorithm carving the maze. `None` leaves it open, with walls only on its border.
+    pub generator: Option<Generator>,
+    /// The probability, between 0 and 1, to open each dead end of the generated maze.
+    pub braid: f64,
+}
+
+/// Rules of the challenge engine.
+#[derive(Debug, Clone)]
+pub struct ChallengeConfig {
+    /// A secret hint is sent to the acting player every `secret_period` actions of its team.
+    /// `0` disables secrets.
+    pub secret_period: u32,
+    /// A challenge replaces the radar view every `challenge_period` moves of a player.
+    /// `0` disables challenges.
+    pub challenge_period: u32,
+    /// Upper bound (exclusive) of the modulo sent with a `SecretSumModulo` challenge.
+    pub max_modulo: u64,
+}
+
+/// Rules of the hints sent with the radar views.
+#[derive(Debug, Clone)]
+pub struct HintConfig {
+    /// A `GridSize` hint replaces the `RelativeCompass` hint every `grid_size_period` views.
+    /// `0` disables grid size hints.
+    pub grid_size_period: u32,
+}
+
+/// Rules of the monsters.
+#[derive(Debug, Clone)]
+pub struct MonsterConfig {
+    /// The number of monsters spawned at the start of the game. `0` disables monsters.
+    pub count: u32,
+    /// The monsters move every `move_period` moves of the players. `0` makes them static.
+    pub move_period: u32,
+    /// The number of teammates, the attacker included, needed next to a monster to defeat it.
+    pub group_size: u32,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            port: 8778,
+            team_size: 3,
+            seed: None,
+            maze_size: (50, 50),
+            maze: MazeConfig::default(),
+            team_fight: false,
+            challenge: ChallengeConfig::default(),
+            hint: HintConfig::default(),
+            monster: MonsterConfig::default(),
+        }
+    }
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        MazeConfig {
+            generator: Some(Generator::RecursiveBacktracking),
+            braid: 0.0,
+        }
+    }
+}
+
+impl Default for ChallengeConfig {
+    fn default() -> Self {
+        ChallengeConfig {
+            secret_period: 4,
+            challenge_period: 10,
+            max_modulo: 1000,
+        }
+    }
+}
+
+impl Default for HintConfig {
+    fn default() -> Self {
+        HintConfig {
+            grid_size_period: 5,
+        }
+    }
+}
+
+impl Default for MonsterConfig {
+    fn default() -> Self {
+        MonsterConfig {
+            count: 0,
+            move_period: 2,
+            group_size: MONSTER_GROUP_SIZE,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Builds the configuration from the command line arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments, without the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is unknown or if its value is missing or invalid.
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> io::Result<Self> {
+        let mut config: ServerConfig = ServerConfig::default();
+
+        while let Some(option) = args.next() {
+            let value: String = args.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Missing value for option {}", option),
+                )
+            })?;
+
+            match option.as_str() {
+                "--port" => config.port = parse_value(&option, &value)?,
+                "--team-size" => config.team_size = parse_value(&option, &value)?,
+                "--seed" => config.seed = Some(parse_value(&option, &value)?),
+                "--maze" => config.maze_size = parse_size(&option, &value)?,
+                "--generator" => config.maze.generator = parse_generator(&option, &value)?,
+                "--braid" => config.maze.braid = parse_value(&option, &value)?,
+                "--team-fight" => config.team_fight = parse_value(&option, &value)?,
+                "--secret-period" => config.challenge.secret_period = parse_value(&option, &value)?,
+                "--challenge-period" => {
+                    config.challenge.challenge_period = parse_value(&option, &value)?
+                }
+                "--max-modulo" => config.challenge.max_modulo = parse_value(&option, &value)?,
+                "--grid-size-period" => {
+                    config.hint.grid_size_period = parse_value(&option, &value)?
+                }
+                "--monsters" => config.monster.count = parse_value(&option, &value)?,
+                "--monster-move-period" => {
+                    config.monster.move_period = parse_value(&option, &value)?
+                }
+                "--monster-group-size" => config.monster.group_size = parse_value(&option, &value)?,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Unknown option {}", option),
+                    ));
+                }
+            }
+        }
+
+        // The modulo is drawn from `2..max_modulo`, which must not be empty.
+        if config.challenge.max_modulo < 3 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--max-modulo must be at least 3",
+            ));
+        }
+
+        if config.maze_size.0 == 0 || config.maze_size.1 == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--maze must have a width and a height greater than 0",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&config.maze.braid) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--braid must be between 0 and 1",
+            ));
+        }
+
+        if config.monster.group_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--monster-group-size must be at least 1",
+            ));
+        }
+
+        return Ok(config);
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: &str) -> io::Result<T> {
+    return value.parse::<T>().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid value '{}' for option {}", value, option),
+        )
+    });
+}
+
+/// Parses the name of a maze generator, `open` meaning no generator.
+fn parse_generator(option: &str, value: &str) -> io::Result<Option<Generator>> {
+    if value == "open" {
+        return Ok(None);
+    }
+    return Ok(Some(parse_value(option, value)?));
+}
+
+/// Parses a size written as `width,height`.
+fn parse_size(option: &str, value: &str) -> io::Result<(u32, u32)> {
+    return match value.split_once(',') {
+        Some((width, height)) => Ok((
+            parse_value(option, width.trim())?,
+            parse_value(option, height.trim())?,
+        )),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid size '{}' for option {}, expected width,height",
+                value, option
+            ),
+        )),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|value: &&str| value.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let config: ServerConfig = ServerConfig::from_args(args(&[
+            "--port",
+            "9000",
+            "--seed",
+            "42",
+            "--challenge-period",
+            "0",
+            "--maze",
+            "20,10",
+            "--monsters",
+            "4",
+            "--team-fight",
+            "true",
+            "--generator",
+            "prims",
+            "--braid",
+            "0.5",
+        ]))
+        .unwrap();
+
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.challenge.challenge_period, 0);
+        assert_eq!(config.maze_size, (20, 10));
+        assert_eq!(config.team_size, 3);
+        assert_eq!(config.monster.count, 4);
+        assert_eq!(config.monster.group_size, 2);
+        assert!(config.team_fight);
+        assert_eq!(config.maze.generator, Some(Generator::Prims));
+        assert_eq!(config.maze.braid, 0.5);
+        assert_eq!(
+            ServerConfig::from_args(args(&["--generator", "open"]))
+                .unwrap()
+                .maze
+                .generator,
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_args_invalid() {
+        assert!(ServerConfig::from_args(args(&["--port"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--port", "abc"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--unknown", "1"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--max-modulo", "1"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--max-modulo", "2"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--max-modulo", "3"])).is_ok());
+        assert!(ServerConfig::from_args(args(&["--maze", "20"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--maze", "0,10"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--monster-group-size", "0"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--generator", "kruskal"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--braid", "1.5"])).is_err());
+    }
+}
@@ -0,0 +1,981 @@
+use grid::generator::{generate, GeneratorOptions};
+use grid::radar::RadarView;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use shared::log_info;
+use shared::types::action::{Action, RelativeDirection};
+use shared::types::cardinal_direction::CardinalDirection;
+use shared::types::error::{ActionError, RegistrationError};
+use shared::types::hint::Hint;
+use shared::types::message::{
+    GameMessage, RegisterTeamResult, SubscribePlayer, SubscribePlayerResult,
+};
+use shared::types::radar_item::{Entity, RadarItem};
+use uuid::Uuid;
+
+use crate::challenge::{ChallengeEngine, RunningChallenge};
+use crate::config::ServerConfig;
+use crate::hint::HintEngine;
+use crate::monster::{is_next_to, MonsterEngine};
+use crate::world::{Position, World};
+
+pub type PlayerId = usize;
+
+pub struct Team {
+    pub name: String,
+    pub registration_token: String,
+    pub members: Vec<PlayerId>,
+    pub action_count: u32,
+}
+
+pub struct Player {
+    pub name: String,
+    pub team_index: usize,
+    /// The cell where the player entered the maze and where it goes back when a monster defeats it.
+    pub spawn: Position,
+    pub position: Position,
+    pub heading: CardinalDirection,
+    pub move_count: u32,
+    pub view_count: u32,
+    pub has_exited: bool,
+    /// The latest secret received by the player, `0` if it never received one.
+    pub secret: u64,
+    pub challenge: Option<RunningChallenge>,
+    /// Cleared when the connection of the player is lost, until it subscribes again to resume its session.
+    pub is_connected: bool,
+}
+
+/// The state of the game shared by all the connections.
+pub struct Game {
+    pub config: ServerConfig,
+    pub teams: Vec<Team>,
+    pub players: Vec<Player>,
+    pub world: World,
+    pub monsters: Vec<Position>,
+    /// The number of moves performed by all the players.
+    pub action_count: u32,
+    challenge_engine: ChallengeEngine,
+    hint_engine: HintEngine,
+    monster_engine: MonsterEngine,
+    rng: StdRng,
+}
+
+impl Game {
+    pub fn new(config: ServerConfig) -> Game {
+        let mut rng: StdRng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        let (columns, rows) = (config.maze_size.0 as usize, config.maze_size.1 as usize);
+        let world: World = match config.maze.generator {
+            Some(generator) => World::from_maze(generate(
+                columns,
+                rows,
+                &GeneratorOptions {
+                    generator,
+                    seed: rng.random(),
+                    braid: config.maze.braid,
+                },
+            )),
+            None => World::new(columns, rows),
+        };
+        let monster_engine: MonsterEngine = MonsterEngine::new(config.monster.clone());
+        // The number of teams is not known yet: the monsters leave room for one full team at least.
+        let monsters: Vec<Position> =
+            monster_engine.spawn(&world, config.team_size as usize, &mut rng);
+
+        Game {
+            world,
+            monsters,
+            action_count: 0,
+            challenge_engine: ChallengeEngine::new(config.challenge.clone()),
+            hint_engine: HintEngine::new(config.hint.clone()),
+            monster_engine,
+            config,
+            teams: Vec::new(),
+            players: Vec::new(),
+            rng,
+        }
+    }
+
+    /// Registers a new team and returns its registration token.
+    pub fn register_team(&mut self, name: &str) -> RegisterTeamResult {
+        if name.trim().is_empty() {
+            return RegisterTeamResult::Err(RegistrationError::InvalidName);
+        }
+        if self.teams.iter().any(|team: &Team| team.name == name) {
+            return RegisterTeamResult::Err(RegistrationError::AlreadyRegistered);
+        }
+
+        let registration_token: String = Uuid::new_v4().to_string();
+        self.teams.push(Team {
+            name: String::from(name),
+            registration_token: registration_token.clone(),
+            members: Vec::new(),
+            action_count: 0,
+        });
+        log_info!("Team '{}' registered", name);
+
+        return RegisterTeamResult::Ok {
+            expected_players: self.config.team_size,
+            registration_token,
+        };
+    }
+
+    /// Subscribes a player to the team owning the registration token.
+    ///
+    /// A player of the team whose connection was lost resumes its session where it was, with the same
+    /// name and token.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the player and the messages to send: the subscription result then the first
+    /// radar view with its hint.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `RegistrationError` to send back if the subscription is refused.
+    pub fn subscribe_player(
+        &mut self,
+        subscribe_player: &SubscribePlayer,
+    ) -> Result<(PlayerId, Vec<GameMessage>), RegistrationError> {
+        if subscribe_player.name.trim().is_empty() {
+            return Err(RegistrationError::InvalidName);
+        }
+
+        let team_index: usize = self
+            .teams
+            .iter()
+            .position(|team: &Team| team.registration_token == subscribe_player.registration_token)
+            .ok_or(RegistrationError::InvalidRegistrationToken)?;
+
+        let team: &Team = &self.teams[team_index];
+        if let Some(&player_id) = team
+            .members
+            .iter()
+            .find(|&&member: &&PlayerId| self.players[member].name == subscribe_player.name)
+        {
+            if self.players[player_id].is_connected {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            self.players[player_id].is_connected = true;
+            log_info!("Player '{}' resumed its session", subscribe_player.name);
+
+            let mut messages: Vec<GameMessage> = vec![GameMessage::SubscribePlayerResult(
+                SubscribePlayerResult::Ok,
+            )];
+            match self.players[player_id].challenge {
+                // The views stay paused until the challenge is solved: the player gets it again.
+                Some(challenge) => messages.push(GameMessage::Challenge(challenge.challenge())),
+                None => messages.extend(self.view_messages(player_id, None)),
+            }
+            return Ok((player_id, messages));
+        }
+        if self.config.team_size as usize <= team.members.len() {
+            return Err(RegistrationError::TooManyPlayers);
+        }
+
+        let free_cells: Vec<Position> = self
+            .world
+            .spawn_positions()
+            .into_iter()
+            .filter(|position: &Position| !self.monsters.contains(position))
+            .collect();
+        // The monsters may fill the maze once several teams have joined: there is no room left.
+        let position: Position = *free_cells
+            .choose(&mut self.rng)
+            .ok_or(RegistrationError::TooManyPlayers)?;
+        let player_id: PlayerId = self.players.len();
+        self.players.push(Player {
+            name: subscribe_player.name.clone(),
+            team_index,
+            spawn: position,
+            position,
+            heading: CardinalDirection::North,
+            move_count: 0,
+            view_count: 0,
+            has_exited: false,
+            secret: 0,
+            challenge: None,
+            is_connected: true,
+        });
+        self.teams[team_index].members.push(player_id);
+        log_info!(
+            "Player '{}' joined team '{}'",
+            subscribe_player.name,
+            self.teams[team_index].name
+        );
+
+        let mut messages: Vec<GameMessage> = vec![GameMessage::SubscribePlayerResult(
+            SubscribePlayerResult::Ok,
+        )];
+        messages.extend(self.view_messages(player_id, None));
+
+        return Ok((player_id, messages));
+    }
+
+    /// Records that the connection of a player is lost: it may subscribe again to resume its session.
+    pub fn disconnect_player(&mut self, player_id: PlayerId) {
+        log_info!("Player '{}' is disconnected", self.players[player_id].name);
+        self.players[player_id].is_connected = false;
+    }
+
+    /// Applies the action of a player and returns the messages to send back, in order.
+    pub fn handle_action(&mut self, player_id: PlayerId, action: Action) -> Vec<GameMessage> {
+        return match action {
+            Action::MoveTo(direction) => self.handle_move(player_id, direction),
+            Action::SolveChallenge { answer } => self.handle_solve_challenge(player_id, &answer),
+        };
+    }
+
+    fn handle_move(
+        &mut self,
+        player_id: PlayerId,
+        direction: RelativeDirection,
+    ) -> Vec<GameMessage> {
+        if self.players[player_id].challenge.is_some() {
+            return vec![GameMessage::ActionError(ActionError::SolveChallengeFirst)];
+        }
+
+        let heading: CardinalDirection = self.players[player_id].heading.turn(direction);
+        let position: Position = match self
+            .world
+            .neighbour(self.players[player_id].position, heading)
+        {
+            Some(position) => position,
+            None => return vec![GameMessage::ActionError(ActionError::CannotPassThroughWall)],
+        };
+
+        if self.config.team_fight && self.is_opponent_at(player_id, position) {
+            return vec![GameMessage::ActionError(
+                ActionError::CannotPassThroughOpponent,
+            )];
+        }
+
+        if let Some(monster_index) = self
+            .monsters
+            .iter()
+            .position(|&monster: &Position| monster == position)
+        {
+            if !self.fight_monster(player_id, monster_index) {
+                let player: &mut Player = &mut self.players[player_id];
+                player.position = player.spawn;
+                player.heading = CardinalDirection::North;
+                player.move_count += 1;
+                // The move counts in the score of the team, even though it ends on the spawn.
+                self.teams[player.team_index].action_count += 1;
+                self.move_monsters_if_due();
+                // Monsters are an extension of the protocol, and so is the error telling the defeat.
+                return vec![GameMessage::ActionError(ActionError::KilledByMonster)];
+            }
+        }
+
+        let mut messages: Vec<GameMessage> = Vec::new();
+        let team_index: usize = self.players[player_id].team_index;
+
+        self.players[player_id].position = position;
+        self.players[player_id].heading = heading;
+        self.players[player_id].move_count += 1;
+        self.teams[team_index].action_count += 1;
+
+        if position == self.world.maze.goal && !self.players[player_id].has_exited {
+            self.players[player_id].has_exited = true;
+            log_info!(
+                "Player '{}' found the exit after {} moves",
+                self.players[player_id].name,
+                self.players[player_id].move_count
+            );
+        }
+        self.move_monsters_if_due();
+
+        let secret: Option<u64> = if self
+            .challenge_engine
+            .is_secret_due(self.teams[team_index].action_count)
+        {
+            let secret: u64 = self.challenge_engine.new_secret(&mut self.rng);
+            self.players[player_id].secret = secret;
+            Some(secret)
+        } else {
+            None
+        };
+
+        if self
+            .challenge_engine
+            .is_challenge_due(self.players[player_id].move_count)
+        {
+            if let Some(secret) = secret {
+                messages.push(GameMessage::Hint(Hint::Secret(secret)));
+            }
+            let (challenge, running_challenge) = self.challenge_engine.new_challenge(&mut self.rng);
+            self.players[player_id].challenge = Some(running_challenge);
+            log_info!(
+                "Player '{}' must solve {:?}",
+                self.players[player_id].name,
+                challenge
+            );
+            messages.push(GameMessage::Challenge(challenge));
+        } else {
+            messages.extend(self.view_messages(player_id, secret));
+        }
+
+        return messages;
+    }
+
+    fn handle_solve_challenge(&mut self, player_id: PlayerId, answer: &str) -> Vec<GameMessage> {
+        let challenge: RunningChallenge = match self.players[player_id].challenge {
+            Some(challenge) => challenge,
+            None => return vec![GameMessage::ActionError(ActionError::NoRunningChallenge)],
+        };
+
+        let team_secrets: Vec<u64> = self.team_secrets(self.players[player_id].team_index);
+        if !ChallengeEngine::is_answer_valid(&challenge, &team_secrets, answer) {
+            return vec![GameMessage::ActionError(
+                ActionError::InvalidChallengeSolution,
+            )];
+        }
+
+        log_info!(
+            "Player '{}' solved its challenge",
+            self.players[player_id].name
+        );
+        self.players[player_id].challenge = None;
+
+        // The radar view paused by the challenge is sent now.
+        return self.view_messages(player_id, None);
+    }
+
+    /// Tells if a player of another team, still in the maze, stands on a cell.
+    fn is_opponent_at(&self, player_id: PlayerId, position: Position) -> bool {
+        let team_index: usize = self.players[player_id].team_index;
+        return self.players.iter().any(|player: &Player| {
+            player.team_index != team_index && !player.has_exited && player.position == position
+        });
+    }
+
+    /// Resolves the attack of a monster by a player.
+    /// The monster is removed if the attacker has enough teammates next to it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the monster is defeated, `false` if the attacker is.
+    fn fight_monster(&mut self, player_id: PlayerId, monster_index: usize) -> bool {
+        let monster: Position = self.monsters[monster_index];
+        let attacker: &Player = &self.players[player_id];
+        let fighters: usize = self.teams[attacker.team_index]
+            .members
+            .iter()
+            .filter(|&&member: &&PlayerId| {
+                let player: &Player = &self.players[member];
+                member == player_id || (!player.has_exited && is_next_to(player.position, monster))
+            })
+            .count();
+
+        if self.monster_engine.is_defeated(fighters) {
+            log_info!(
+                "Player '{}' and {} teammate(s) defeated the monster at {:?}",
+                attacker.name,
+                fighters - 1,
+                monster
+            );
+            self.monsters.remove(monster_index);
+            return true;
+        }
+
+        log_info!(
+            "Player '{}' was defeated by the monster at {:?} and goes back to {:?}",
+            attacker.name,
+            monster,
+            attacker.spawn
+        );
+        return false;
+    }
+
+    /// Counts a move of any player and moves the monsters when it is their turn.
+    fn move_monsters_if_due(&mut self) {
+        self.action_count += 1;
+        if !self.monster_engine.is_move_due(self.action_count) {
+            return;
+        }
+
+        let players: Vec<Position> = self
+            .players
+            .iter()
+            .filter(|player: &&Player| !player.has_exited)
+            .map(|player: &Player| player.position)
+            .collect();
+        self.monster_engine
+            .wander(&self.world, &mut self.monsters, &players, &mut self.rng);
+    }
+
+    /// Returns the latest secret of each member of the team.
+    pub fn team_secrets(&self, team_index: usize) -> Vec<u64> {
+        return self.teams[team_index]
+            .members
+            .iter()
+            .map(|&member: &PlayerId| self.players[member].secret)
+            .collect();
+    }
+
+    /// Returns the current radar view of the player preceded by its hint.
+    /// The hint is the new secret of the player if it received one, otherwise it is chosen by the hint engine.
+    fn view_messages(&mut self, player_id: PlayerId, secret: Option<u64>) -> Vec<GameMessage> {
+        self.players[player_id].view_count += 1;
+
+        let player: &Player = &self.players[player_id];
+        let hint: Hint = match secret {
+            Some(secret) => Hint::Secret(secret),
+            None => self.hint_engine.view_hint(
+                player.view_count,
+                &self.world,
+                player.position,
+                player.heading,
+            ),
+        };
+
+        return vec![
+            GameMessage::Hint(hint),
+            GameMessage::RadarView(self.radar_view(player_id)),
+        ];
+    }
+
+    /// Returns the encoded radar view of the player.
+    fn radar_view(&self, player_id: PlayerId) -> String {
+        let player: &Player = &self.players[player_id];
+        return RadarView::capture_visible(
+            (player.position.0 as isize, player.position.1 as isize),
+            player.heading,
+            |cell: (isize, isize), direction: CardinalDirection| self.world.wall(cell, direction),
+            |cell: (isize, isize)| self.radar_item(player_id, cell),
+        );
+    }
+
+    /// Returns the content of a cell as seen by a player, `None` if the cell is outside of the maze.
+    fn radar_item(&self, viewer: PlayerId, cell: (isize, isize)) -> Option<RadarItem> {
+        if !self.world.contains(cell) {
+            return None;
+        }
+
+        let position: Position = (cell.0 as usize, cell.1 as usize);
+        let entity: Option<Entity> = if self.monsters.contains(&position) {
+            Some(Entity::Monster)
+        } else {
+            self.players
+                .iter()
+                .enumerate()
+                .find(|(id, player): &(PlayerId, &Player)| {
+                    *id != viewer && !player.has_exited && player.position == position
+                })
+                .map(|(_, player): (PlayerId, &Player)| {
+                    if player.team_index == self.players[viewer].team_index {
+                        Entity::Ally
+                    } else {
+                        Entity::Enemy
+                    }
+                })
+        };
+
+        return Some(RadarItem {
+            is_hint: false,
+            is_goal: position == self.world.maze.goal,
+            entity,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ChallengeConfig, MazeConfig, MonsterConfig};
+    use grid::maze::Maze;
+    use shared::types::challenge::Challenge;
+
+    fn game(secret_period: u32, challenge_period: u32) -> Game {
+        Game::new(ServerConfig {
+            seed: Some(1),
+            challenge: ChallengeConfig {
+                secret_period,
+                challenge_period,
+                max_modulo: 1000,
+            },
+            maze: MazeConfig {
+                generator: None,
+                braid: 0.0,
+            },
+            ..ServerConfig::default()
+        })
+    }
+
+    fn subscribe(game: &mut Game, name: &str) -> PlayerId {
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let (player_id, _) = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from(name),
+                registration_token,
+            })
+            .unwrap();
+        // Spawns far from the borders so that the tests can move freely.
+        game.players[player_id].position = (25, 25);
+        return player_id;
+    }
+
+    fn move_front() -> Action {
+        Action::MoveTo(RelativeDirection::Front)
+    }
+
+    fn solve(answer: u64) -> Action {
+        Action::SolveChallenge {
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_registration_errors() {
+        let mut game: Game = game(0, 0);
+        assert!(matches!(
+            game.register_team(""),
+            RegisterTeamResult::Err(RegistrationError::InvalidName)
+        ));
+        assert!(matches!(
+            game.register_team("team"),
+            RegisterTeamResult::Ok {
+                expected_players: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            game.register_team("team"),
+            RegisterTeamResult::Err(RegistrationError::AlreadyRegistered)
+        ));
+
+        let invalid_token: Result<(PlayerId, Vec<GameMessage>), RegistrationError> = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 1"),
+                registration_token: String::from("invalid"),
+            });
+        assert!(matches!(
+            invalid_token,
+            Err(RegistrationError::InvalidRegistrationToken)
+        ));
+
+        subscribe(&mut game, "Player 1");
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let duplicate: Result<(PlayerId, Vec<GameMessage>), RegistrationError> = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 1"),
+                registration_token,
+            });
+        assert!(matches!(
+            duplicate,
+            Err(RegistrationError::AlreadyRegistered)
+        ));
+
+        subscribe(&mut game, "Player 2");
+        subscribe(&mut game, "Player 3");
+        game.disconnect_player(1);
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let resumed: Result<(PlayerId, Vec<GameMessage>), RegistrationError> = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 2"),
+                registration_token,
+            });
+        assert!(matches!(
+            resumed
+                .as_ref()
+                .map(|(player_id, messages)| (*player_id, messages.as_slice())),
+            Ok((
+                1,
+                [
+                    GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok),
+                    GameMessage::Hint(_),
+                    GameMessage::RadarView(_)
+                ]
+            ))
+        ));
+        assert_eq!(game.players[1].position, (25, 25));
+
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let too_many: Result<(PlayerId, Vec<GameMessage>), RegistrationError> = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 4"),
+                registration_token,
+            });
+        assert!(matches!(too_many, Err(RegistrationError::TooManyPlayers)));
+    }
+
+    #[test]
+    fn test_no_running_challenge() {
+        let mut game: Game = game(0, 0);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+
+        let messages: Vec<GameMessage> = game.handle_action(player, solve(0));
+        assert!(matches!(
+            messages.as_slice(),
+            [GameMessage::ActionError(ActionError::NoRunningChallenge)]
+        ));
+    }
+
+    #[test]
+    fn test_secret_hint_comes_with_the_view() {
+        let mut game: Game = game(1, 0);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+
+        let messages: Vec<GameMessage> = game.handle_action(player, move_front());
+        match messages.as_slice() {
+            [GameMessage::Hint(Hint::Secret(secret)), GameMessage::RadarView(_)] => {
+                assert_eq!(game.players[player].secret, *secret);
+            }
+            _ => panic!("Expected a secret then a radar view, got {:?}", messages),
+        }
+    }
+
+    #[test]
+    fn test_challenge_pauses_the_views() {
+        let mut game: Game = game(0, 2);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+
+        assert!(matches!(
+            game.handle_action(player, move_front()).as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+        assert!(matches!(
+            game.handle_action(player, move_front()).as_slice(),
+            [GameMessage::Challenge(Challenge::SecretSumModulo(_))]
+        ));
+
+        // No view is sent while the challenge is running.
+        assert!(matches!(
+            game.handle_action(player, move_front()).as_slice(),
+            [GameMessage::ActionError(ActionError::SolveChallengeFirst)]
+        ));
+
+        // No secret was ever sent: the sum is 0.
+        assert!(matches!(
+            game.handle_action(player, solve(1)).as_slice(),
+            [GameMessage::ActionError(
+                ActionError::InvalidChallengeSolution
+            )]
+        ));
+        assert!(matches!(
+            game.handle_action(player, solve(0)).as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+        assert!(game.players[player].challenge.is_none());
+    }
+
+    #[test]
+    fn test_resume_session() {
+        let mut game: Game = game(0, 0);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        game.handle_action(player, Action::MoveTo(RelativeDirection::Right));
+        let (position, heading) = (game.players[player].position, game.players[player].heading);
+
+        // The player gets the view of where it stands, its session going on.
+        game.disconnect_player(player);
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let (resumed, messages) = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 1"),
+                registration_token,
+            })
+            .unwrap();
+        assert_eq!(resumed, player);
+        let expected_view: String = game.radar_view(player);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok),
+                GameMessage::Hint(_),
+                GameMessage::RadarView(view)
+            ] if *view == expected_view
+        ));
+        assert_eq!(game.players[player].position, position);
+        assert_eq!(game.players[player].heading, heading);
+        assert!(game.players[player].is_connected);
+    }
+
+    #[test]
+    fn test_resume_during_challenge() {
+        let mut game: Game = game(0, 1);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        let modulo: u64 = match game.handle_action(player, move_front()).as_slice() {
+            [GameMessage::Challenge(Challenge::SecretSumModulo(modulo))] => *modulo,
+            messages => panic!("Expected a challenge, got {:?}", messages),
+        };
+
+        // The challenge is sent again instead of the paused view.
+        game.disconnect_player(player);
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let (resumed, messages) = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Player 1"),
+                registration_token,
+            })
+            .unwrap();
+        assert_eq!(resumed, player);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok),
+                GameMessage::Challenge(Challenge::SecretSumModulo(resent))
+            ] if *resent == modulo
+        ));
+        assert!(matches!(
+            game.handle_action(player, solve(0)).as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+    }
+
+    #[test]
+    fn test_stale_answer_is_rejected() {
+        let mut game: Game = game(0, 1);
+        game.register_team("team");
+        let player_1: PlayerId = subscribe(&mut game, "Player 1");
+        let player_2: PlayerId = subscribe(&mut game, "Player 2");
+        game.players[player_1].secret = 11;
+        game.players[player_2].secret = 31;
+
+        let modulo: u64 = match game.handle_action(player_1, move_front()).as_slice() {
+            [GameMessage::Challenge(Challenge::SecretSumModulo(modulo))] => *modulo,
+            messages => panic!("Expected a challenge, got {:?}", messages),
+        };
+        let stale_answer: u64 = (11 + 31) % modulo;
+
+        // The teammate receives a new secret before the answer is sent.
+        game.players[player_2].secret = 32;
+
+        assert!(matches!(
+            game.handle_action(player_1, solve(stale_answer)).as_slice(),
+            [GameMessage::ActionError(
+                ActionError::InvalidChallengeSolution
+            )]
+        ));
+        assert!(matches!(
+            game.handle_action(player_1, solve((11 + 32) % modulo))
+                .as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+    }
+
+    #[test]
+    fn test_every_view_comes_with_a_hint() {
+        let mut game: Game = game(3, 0);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+
+        for _ in 0..20 {
+            let messages: Vec<GameMessage> =
+                game.handle_action(player, Action::MoveTo(RelativeDirection::Right));
+            assert!(
+                matches!(
+                    messages.as_slice(),
+                    [GameMessage::Hint(_), GameMessage::RadarView(_)]
+                ),
+                "Expected a hint then a radar view, got {:?}",
+                messages
+            );
+        }
+    }
+
+    #[test]
+    fn test_move_and_compass() {
+        let mut game: Game = game(0, 0);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        game.players[player].position = (49, 20);
+
+        // Turning right makes the player face the East border.
+        match game
+            .handle_action(player, Action::MoveTo(RelativeDirection::Right))
+            .as_slice()
+        {
+            [GameMessage::ActionError(ActionError::CannotPassThroughWall)] => {}
+            messages => panic!("Expected a wall, got {:?}", messages),
+        }
+        assert_eq!(game.players[player].position, (49, 20));
+        assert_eq!(game.players[player].heading, CardinalDirection::North);
+
+        // The goal is straight to the South.
+        match game
+            .handle_action(player, Action::MoveTo(RelativeDirection::Back))
+            .as_slice()
+        {
+            [GameMessage::Hint(Hint::RelativeCompass { angle }), GameMessage::RadarView(_)] => {
+                assert_eq!(*angle, 0.0);
+            }
+            messages => panic!("Expected a compass then a radar view, got {:?}", messages),
+        }
+        assert_eq!(game.players[player].position, (49, 21));
+        assert_eq!(game.players[player].heading, CardinalDirection::South);
+        assert_eq!(game.players[player].move_count, 1);
+    }
+
+    /// A game with static monsters, so that the tests place them.
+    fn game_with_monsters(group_size: u32) -> Game {
+        let mut game: Game = Game::new(ServerConfig {
+            seed: Some(1),
+            challenge: ChallengeConfig {
+                secret_period: 0,
+                challenge_period: 0,
+                max_modulo: 1000,
+            },
+            monster: MonsterConfig {
+                count: 0,
+                move_period: 0,
+                group_size,
+            },
+            maze: MazeConfig {
+                generator: None,
+                braid: 0.0,
+            },
+            ..ServerConfig::default()
+        });
+        game.register_team("team");
+        return game;
+    }
+
+    #[test]
+    fn test_spawn_on_a_free_cell() {
+        let mut game: Game = game_with_monsters(2);
+        game.world = World::new(2, 2);
+        game.monsters = vec![(0, 0), (1, 0), (0, 1)];
+        let registration_token: String = game.teams[0].registration_token.clone();
+        let subscribe_player: SubscribePlayer = SubscribePlayer {
+            name: String::from("Player 1"),
+            registration_token,
+        };
+
+        // The monsters hold every cell but the exit.
+        assert!(matches!(
+            game.subscribe_player(&subscribe_player),
+            Err(RegistrationError::TooManyPlayers)
+        ));
+
+        game.monsters.pop();
+        let (player, _) = game.subscribe_player(&subscribe_player).unwrap();
+        assert_eq!(game.players[player].position, (0, 1));
+    }
+
+    /// Returns the view of a player in the center of a 5x5 maze, facing North.
+    fn view_in(maze: Maze) -> String {
+        let mut game: Game = game(0, 0);
+        game.world = World::from_maze(maze);
+        game.register_team("team");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        game.players[player].position = (2, 2);
+        game.players[player].heading = CardinalDirection::North;
+        return game.radar_view(player);
+    }
+
+    #[test]
+    fn test_radar_view_occlusion() {
+        // The game sends the views hidden by the walls next to the player, as the reference server does.
+        // `RadarView::capture_visible` checks the other reference views.
+        let mut maze: Maze = Maze::new(5, 5);
+        maze.set_wall((2, 1), CardinalDirection::North, false);
+        maze.set_wall((2, 2), CardinalDirection::North, false);
+        maze.set_wall((2, 2), CardinalDirection::West, false);
+        maze.set_wall((1, 3), CardinalDirection::North, false);
+        assert_eq!(view_in(maze), "ieysGjGO8papd/a");
+    }
+
+    #[test]
+    fn test_monster_is_in_the_view() {
+        let mut game: Game = game_with_monsters(2);
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        game.players[player].spawn = (25, 25);
+        game.monsters = vec![(25, 23)];
+
+        let radar_view: String = match game.handle_action(player, move_front()).as_slice() {
+            [GameMessage::Hint(_), GameMessage::RadarView(radar_view)] => radar_view.clone(),
+            messages => panic!("Expected a hint then a radar view, got {:?}", messages),
+        };
+        let grid: Vec<Vec<String>> = RadarView::new(radar_view, CardinalDirection::North).grid;
+        assert_eq!(grid[1][3], "M");
+    }
+
+    #[test]
+    fn test_lone_player_is_killed_by_monster() {
+        let mut game: Game = game_with_monsters(2);
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        game.players[player].spawn = (10, 10);
+        game.monsters = vec![(25, 24)];
+
+        assert!(matches!(
+            game.handle_action(player, move_front()).as_slice(),
+            [GameMessage::ActionError(ActionError::KilledByMonster)]
+        ));
+        assert_eq!(game.players[player].position, (10, 10));
+        assert_eq!(game.players[player].heading, CardinalDirection::North);
+        assert_eq!(game.players[player].move_count, 1);
+        assert_eq!(game.teams[game.players[player].team_index].action_count, 1);
+        assert_eq!(game.monsters, vec![(25, 24)]);
+    }
+
+    #[test]
+    fn test_grouped_players_defeat_monster() {
+        let mut game: Game = game_with_monsters(2);
+        let player_1: PlayerId = subscribe(&mut game, "Player 1");
+        let player_2: PlayerId = subscribe(&mut game, "Player 2");
+        game.players[player_2].position = (26, 24);
+        game.monsters = vec![(25, 24)];
+
+        assert!(matches!(
+            game.handle_action(player_1, move_front()).as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+        assert_eq!(game.players[player_1].position, (25, 24));
+        assert!(game.monsters.is_empty());
+    }
+
+    #[test]
+    fn test_team_fight() {
+        let mut game: Game = game(0, 0);
+        game.config.team_fight = true;
+        game.register_team("team");
+        game.register_team("opponents");
+        let player: PlayerId = subscribe(&mut game, "Player 1");
+        let teammate: PlayerId = subscribe(&mut game, "Player 2");
+        let registration_token: String = game.teams[1].registration_token.clone();
+        let (opponent, _) = game
+            .subscribe_player(&SubscribePlayer {
+                name: String::from("Opponent"),
+                registration_token,
+            })
+            .unwrap();
+        game.players[opponent].position = (25, 24);
+        game.players[teammate].position = (24, 25);
+
+        assert!(matches!(
+            game.handle_action(player, move_front()).as_slice(),
+            [GameMessage::ActionError(
+                ActionError::CannotPassThroughOpponent
+            )]
+        ));
+        assert_eq!(game.players[player].position, (25, 25));
+        assert_eq!(game.players[player].move_count, 0);
+
+        // A teammate's cell can be shared.
+        assert!(matches!(
+            game.handle_action(player, Action::MoveTo(RelativeDirection::Left))
+                .as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+        assert_eq!(game.players[player].position, (24, 25));
+
+        // Without team fight, opponents share cells too.
+        game.config.team_fight = false;
+        game.players[opponent].position = (24, 24);
+        assert!(matches!(
+            game.handle_action(player, Action::MoveTo(RelativeDirection::Right))
+                .as_slice(),
+            [GameMessage::Hint(_), GameMessage::RadarView(_)]
+        ));
+        assert_eq!(game.players[player].position, (24, 24));
+    }
+}
@@ -0,0 +1,120 @@
+use shared::types::cardinal_direction::CardinalDirection;
+use shared::types::hint::Hint;
+
+use crate::config::HintConfig;
+use crate::world::{Position, World};
+
+/// Chooses the hint sent with each radar view.
+pub struct HintEngine {
+    config: HintConfig,
+}
+
+impl HintEngine {
+    pub fn new(config: HintConfig) -> HintEngine {
+        HintEngine { config }
+    }
+
+    /// Returns the hint to send with a radar view.
+    ///
+    /// # Arguments
+    ///
+    /// * `view_count` - The number of views sent to the player, including the current one.
+    /// * `world` - The world the player is in.
+    /// * `position` - The position of the player.
+    /// * `heading` - The direction the player is facing.
+    pub fn view_hint(
+        &self,
+        view_count: u32,
+        world: &World,
+        position: Position,
+        heading: CardinalDirection,
+    ) -> Hint {
+        if self.config.grid_size_period != 0
+            && view_count.is_multiple_of(self.config.grid_size_period)
+        {
+            return Hint::GridSize {
+                columns: world.maze.columns as u32,
+                rows: world.maze.rows as u32,
+            };
+        }
+
+        return Hint::RelativeCompass {
+            angle: relative_compass_angle(position, heading, world.maze.goal),
+        };
+    }
+}
+
+/// Computes the direction of the goal seen from the player, in degrees.
+///
+/// `0` means straight ahead and `90` means on the right. The angle is in `]-180, 180]`.
+pub fn relative_compass_angle(
+    position: Position,
+    heading: CardinalDirection,
+    goal: Position,
+) -> f32 {
+    let delta_column: f32 = goal.0 as f32 - position.0 as f32;
+    let delta_row: f32 = goal.1 as f32 - position.1 as f32;
+    if delta_column == 0.0 && delta_row == 0.0 {
+        return 0.0;
+    }
+
+    // Rows grow towards the South, so the North is the opposite of the row axis.
+    let absolute_angle: f32 = delta_column.atan2(-delta_row).to_degrees();
+    let mut relative_angle: f32 = (absolute_angle - heading.degrees()) % 360.0;
+    if relative_angle <= -180.0 {
+        relative_angle += 360.0;
+    } else if 180.0 < relative_angle {
+        relative_angle -= 360.0;
+    }
+
+    return relative_angle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_angle(position: Position, heading: CardinalDirection, goal: Position, angle: f32) {
+        let computed: f32 = relative_compass_angle(position, heading, goal);
+        assert!(
+            (computed - angle).abs() < 1e-4,
+            "expected {} but computed {}",
+            angle,
+            computed
+        );
+    }
+
+    #[test]
+    fn test_relative_compass_angle() {
+        assert_angle((2, 2), CardinalDirection::North, (2, 0), 0.0);
+        assert_angle((2, 2), CardinalDirection::North, (4, 2), 90.0);
+        assert_angle((2, 2), CardinalDirection::North, (0, 2), -90.0);
+        assert_angle((2, 2), CardinalDirection::North, (2, 4), 180.0);
+        assert_angle((2, 2), CardinalDirection::North, (4, 4), 135.0);
+        assert_angle((2, 2), CardinalDirection::East, (4, 4), 45.0);
+        assert_angle((2, 2), CardinalDirection::South, (4, 4), -45.0);
+        assert_angle((2, 2), CardinalDirection::West, (4, 4), -135.0);
+        assert_angle((2, 2), CardinalDirection::West, (2, 0), 90.0);
+        assert_angle((2, 2), CardinalDirection::East, (2, 2), 0.0);
+    }
+
+    #[test]
+    fn test_view_hint() {
+        let engine: HintEngine = HintEngine::new(HintConfig {
+            grid_size_period: 3,
+        });
+        let world: World = World::new(10, 20);
+
+        assert!(matches!(
+            engine.view_hint(1, &world, (0, 0), CardinalDirection::North),
+            Hint::RelativeCompass { .. }
+        ));
+        assert!(matches!(
+            engine.view_hint(3, &world, (0, 0), CardinalDirection::North),
+            Hint::GridSize {
+                columns: 10,
+                rows: 20
+            }
+        ));
+    }
+}
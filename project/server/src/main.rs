@@ -1,23 +1,62 @@
-use shared::types::message::{GameMessage, RegisterTeamResult, SubscribePlayerResult};
-use shared::{log_debug, log_error, log_info};
-use std::io::Error as IoError;
+mod challenge;
+mod config;
+mod game;
+mod hint;
+mod monster;
+mod world;
+
+use config::ServerConfig;
+use game::{Game, PlayerId};
+use shared::types::log::log::Log;
+use shared::types::message::{GameMessage, SubscribePlayerResult};
+use shared::{log_debug, log_error, log_info, log_warning};
+use std::env;
+use std::io::{self, Error as IoError};
 use std::net::{TcpListener, TcpStream};
-use uuid::Uuid;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
 
 fn main() {
-    log_debug!("Server is running on localhost:8778");
-    let listener = TcpListener::bind("127.0.0.1:8778").unwrap();
+    if let Err(e) = Log::init_from_env() {
+        log_error!("Failed to configure the logs: {}", e);
+    }
+
+    let config: ServerConfig = match ServerConfig::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            log_error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    run(config);
+}
+
+/// Listens for connections and serves each of them in its own thread.
+fn run(config: ServerConfig) {
+    let address: String = format!("127.0.0.1:{}", config.port);
+    log_info!("Server is running on {}", address);
+    let listener = TcpListener::bind(&address).unwrap();
+    let game: Arc<Mutex<Game>> = Arc::new(Mutex::new(Game::new(config)));
 
     for stream in listener.incoming() {
         match stream {
-            Ok(mut stream) => match handle_connection(&mut stream) {
-                Ok(_) => {
-                    log_debug!("Connection closed: {:?}", stream.peer_addr());
-                }
-                Err(e) => {
-                    log_error!("ERROR Failed to handle connection: {:?}", e);
-                }
-            },
+            Ok(mut stream) => {
+                let game: Arc<Mutex<Game>> = Arc::clone(&game);
+                thread::spawn(move || {
+                    if let Ok(peer) = stream.peer_addr() {
+                        Log::set_context("peer", &peer.to_string());
+                    }
+                    match handle_connection(&mut stream, &game) {
+                        Ok(_) => {
+                            log_debug!("Connection closed: {:?}", stream.peer_addr());
+                        }
+                        Err(e) => {
+                            log_error!("ERROR Failed to handle connection: {:?}", e);
+                        }
+                    }
+                });
+            }
             Err(e) => {
                 log_error!("ERROR Failed to establish connection: {:?}", e);
             }
@@ -25,45 +64,89 @@ fn main() {
     }
 }
 
-fn handle_connection(stream: &mut TcpStream) -> Result<(), IoError> {
+/// Serves the messages of a connection until the client closes it, then marks its player as disconnected
+/// so that it can resume its session on a new connection.
+fn handle_connection(stream: &mut TcpStream, game: &Arc<Mutex<Game>>) -> Result<(), IoError> {
     log_info!("New connection: {:?}", stream.peer_addr());
-    match GameMessage::receive(stream) {
-        Ok(GameMessage::RegisterTeam(register_team)) => {
-            log_info!("Registering team: {:?}", register_team);
-            let register_team_result = RegisterTeamResult::Ok {
-                expected_players: 1,
-                registration_token: Uuid::new_v4().to_string(),
-            };
-            let response = GameMessage::RegisterTeamResult(register_team_result);
-            response.send(stream)?;
-        }
-        Ok(GameMessage::SubscribePlayer(subscribe_player)) => {
-            log_info!("Subscribing player: {:?}", subscribe_player);
-            let subscribe_player_result = SubscribePlayerResult::Ok;
-            let response = GameMessage::SubscribePlayerResult(subscribe_player_result);
+    let mut player_id: Option<PlayerId> = None;
+    let result: Result<(), IoError> = serve_messages(stream, game, &mut player_id);
+    if let Some(id) = player_id {
+        lock_game(game).disconnect_player(id);
+    }
+    return result;
+}
+
+/// Serves the messages of a connection until the client closes it.
+fn serve_messages(
+    stream: &mut TcpStream,
+    game: &Arc<Mutex<Game>>,
+    player_id: &mut Option<PlayerId>,
+) -> Result<(), IoError> {
+    loop {
+        let message: GameMessage = match GameMessage::receive(stream) {
+            Ok(message) => message,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let responses: Vec<GameMessage> = {
+            let mut game: MutexGuard<'_, Game> = lock_game(game);
+            match (message, *player_id) {
+                (GameMessage::RegisterTeam(register_team), _) => {
+                    log_info!("Registering team: {:?}", register_team);
+                    vec![GameMessage::RegisterTeamResult(
+                        game.register_team(&register_team.name),
+                    )]
+                }
+                (GameMessage::SubscribePlayer(subscribe_player), None) => {
+                    log_info!("Subscribing player: {:?}", subscribe_player);
+                    match game.subscribe_player(&subscribe_player) {
+                        Ok((id, messages)) => {
+                            *player_id = Some(id);
+                            messages
+                        }
+                        Err(e) => vec![GameMessage::SubscribePlayerResult(
+                            SubscribePlayerResult::Err(e),
+                        )],
+                    }
+                }
+                (GameMessage::Action(action), Some(id)) => {
+                    log_info!("Action received: {:?}", action);
+                    game.handle_action(id, action)
+                }
+                (message, _) => {
+                    log_error!(
+                        "Invalid request from {:?}: {:?}",
+                        stream.peer_addr(),
+                        message
+                    );
+                    return Ok(());
+                }
+            }
+        };
+
+        for response in responses {
+            log_debug!("Sending: {:?}", response);
             response.send(stream)?;
-            let radar_view = GameMessage::RadarView("jiucAjGa//cpapa".to_string());
-            log_info!("Sending radar view: {:?}", radar_view);
-            radar_view.send(stream)?;
-        }
-        Ok(GameMessage::Action(action)) => {
-            log_info!("Action received: {:?}", action);
-            let radar_view = GameMessage::RadarView("jiucAjGa//cpapa".to_string());
-            log_info!("Sending radar view: {:?}", radar_view);
-            radar_view.send(stream)?;
-        }
-        _ => {
-            log_error!("Invalid request: {:?}", stream.peer_addr());
         }
     }
-    return Ok(());
+}
+
+fn lock_game(game: &Arc<Mutex<Game>>) -> MutexGuard<'_, Game> {
+    return match game.lock() {
+        Ok(game) => game,
+        Err(poisoned) => {
+            log_warning!("Game mutex is poisoned. The game state may be corrupted");
+            poisoned.into_inner()
+        }
+    };
 }
 
 #[test]
 fn test_register_team() {
     // do a server in a separate thread then call it
     std::thread::spawn(|| {
-        main();
+        run(ServerConfig::default());
     });
     std::thread::sleep(std::time::Duration::from_millis(100));
     let mut stream = TcpStream::connect("127.0.0.1:8778").unwrap();
@@ -73,16 +156,13 @@ fn test_register_team() {
     register_team.send(&mut stream).unwrap();
     let response = GameMessage::receive(&mut stream).unwrap();
     match response {
-        GameMessage::RegisterTeamResult(register_team_result) => match register_team_result {
-            RegisterTeamResult::Ok {
-                expected_players,
-                registration_token,
-            } => {
-                assert_eq!(expected_players, 1);
-                assert_eq!(registration_token.len(), 36);
-            }
-            _ => panic!("Invalid response"),
-        },
+        GameMessage::RegisterTeamResult(shared::types::message::RegisterTeamResult::Ok {
+            expected_players,
+            registration_token,
+        }) => {
+            assert_eq!(expected_players, 3);
+            assert_eq!(registration_token.len(), 36);
+        }
         _ => panic!("Invalid response"),
     }
 }
@@ -0,0 +1,151 @@
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+use crate::config::MonsterConfig;
+use crate::world::{Position, World};
+
+/// Spawns and moves the monsters and decides who wins a fight.
+///
+/// A player fights a monster by moving into its cell. The teammates standing on a cell next to
+/// the monster fight with it: if they are enough, the monster is defeated, otherwise the attacker
+/// loses and is sent back to its spawn.
+pub struct MonsterEngine {
+    config: MonsterConfig,
+}
+
+impl MonsterEngine {
+    pub fn new(config: MonsterConfig) -> MonsterEngine {
+        MonsterEngine { config }
+    }
+
+    /// Places the monsters on random free cells, never on the exit.
+    ///
+    /// The monsters leave at least `reserved` cells free, so that the players can still spawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The world the monsters live in.
+    /// * `reserved` - The number of cells kept free for the players.
+    /// * `rng` - The random generator of the game.
+    pub fn spawn(&self, world: &World, reserved: usize, rng: &mut StdRng) -> Vec<Position> {
+        let free_cells: usize = world.spawn_positions().len().saturating_sub(reserved);
+        let count: usize = (self.config.count as usize).min(free_cells);
+        let mut monsters: Vec<Position> = Vec::with_capacity(count);
+
+        while monsters.len() < count {
+            let position: Position = world.random_position(rng);
+            if !monsters.contains(&position) {
+                monsters.push(position);
+            }
+        }
+
+        return monsters;
+    }
+
+    /// Tells if the monsters move after an action.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - The number of moves performed by all the players, including the current one.
+    pub fn is_move_due(&self, actions: u32) -> bool {
+        return self.config.move_period != 0 && actions.is_multiple_of(self.config.move_period);
+    }
+
+    /// Moves each monster to a random free cell next to it. A monster with no free cell around stays.
+    /// Monsters never walk into a player: the fights are always started by the players.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The world the monsters live in.
+    /// * `monsters` - The positions of the monsters.
+    /// * `players` - The positions of the players still in the maze.
+    /// * `rng` - The random generator of the game.
+    pub fn wander(
+        &self,
+        world: &World,
+        monsters: &mut [Position],
+        players: &[Position],
+        rng: &mut StdRng,
+    ) {
+        for index in 0..monsters.len() {
+            let free_cells: Vec<Position> = world
+                .neighbours(monsters[index])
+                .into_iter()
+                .filter(|cell: &Position| !players.contains(cell) && !monsters.contains(cell))
+                .collect();
+
+            if let Some(&cell) = free_cells.choose(rng) {
+                monsters[index] = cell;
+            }
+        }
+    }
+
+    /// Tells if a monster is defeated.
+    ///
+    /// # Arguments
+    ///
+    /// * `fighters` - The attacker and its teammates standing next to the monster.
+    pub fn is_defeated(&self, fighters: usize) -> bool {
+        return self.config.group_size as usize <= fighters;
+    }
+}
+
+/// Tells if two cells are the same or share a side.
+pub fn is_next_to(cell: Position, other: Position) -> bool {
+    return cell.0.abs_diff(other.0) + cell.1.abs_diff(other.1) <= 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn new_engine(count: u32) -> MonsterEngine {
+        MonsterEngine::new(MonsterConfig {
+            count,
+            move_period: 2,
+            group_size: 2,
+        })
+    }
+
+    #[test]
+    fn test_spawn() {
+        let world: World = World::new(2, 2);
+        let mut rng: StdRng = StdRng::seed_from_u64(3);
+
+        let monsters: Vec<Position> = new_engine(10).spawn(&world, 0, &mut rng);
+        assert_eq!(monsters.len(), 3);
+        assert!(!monsters.contains(&world.maze.goal));
+
+        // The cells kept for the players stay free.
+        let monsters: Vec<Position> = new_engine(10).spawn(&world, 2, &mut rng);
+        assert_eq!(monsters.len(), 1);
+    }
+
+    #[test]
+    fn test_wander() {
+        // A corridor: the monster in the middle can only go to the East.
+        let world: World = World::new(3, 1);
+        let mut rng: StdRng = StdRng::seed_from_u64(3);
+        let mut monsters: Vec<Position> = vec![(1, 0)];
+
+        new_engine(1).wander(&world, &mut monsters, &[(0, 0)], &mut rng);
+        assert_eq!(monsters, vec![(2, 0)]);
+
+        // Surrounded by players, it stays.
+        new_engine(1).wander(&world, &mut monsters, &[(1, 0)], &mut rng);
+        assert_eq!(monsters, vec![(2, 0)]);
+    }
+
+    #[test]
+    fn test_rules() {
+        let engine: MonsterEngine = new_engine(1);
+        assert!(!engine.is_move_due(1));
+        assert!(engine.is_move_due(2));
+        assert!(!engine.is_defeated(1));
+        assert!(engine.is_defeated(2));
+        assert!(is_next_to((1, 1), (1, 2)));
+        assert!(is_next_to((1, 1), (1, 1)));
+        assert!(!is_next_to((1, 1), (2, 2)));
+    }
+}
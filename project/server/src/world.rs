@@ -0,0 +1,126 @@
+use grid::maze::Maze;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::types::cardinal_direction::CardinalDirection;
+
+/// A cell of the maze: (column, row), the row growing towards the South.
+pub type Position = (usize, usize);
+
+/// The geometry of the game: the walls of the maze and the position of the exit.
+pub struct World {
+    pub maze: Maze,
+}
+
+impl World {
+    /// Creates a world without any wall but its border, whose exit is in the bottom right cell.
+    pub fn new(columns: usize, rows: usize) -> World {
+        return World::from_maze(Maze::open(columns, rows));
+    }
+
+    /// Creates a world from a generated maze.
+    pub fn from_maze(maze: Maze) -> World {
+        return World { maze };
+    }
+
+    /// Returns the cell next to `position` in `direction`, or `None` if a wall is in the way.
+    pub fn neighbour(&self, position: Position, direction: CardinalDirection) -> Option<Position> {
+        return self.maze.open_neighbour(position, direction);
+    }
+
+    /// Tells if a cell, whose coordinates may be negative, is inside the maze.
+    pub fn contains(&self, cell: (isize, isize)) -> bool {
+        return self.maze.contains(cell);
+    }
+
+    /// Tells if there is a wall on a side of a cell, as seen by the radar.
+    /// The border of the maze is a wall. The walls between two cells outside of the maze are undefined.
+    pub fn wall(&self, cell: (isize, isize), direction: CardinalDirection) -> Option<bool> {
+        return self.maze.wall(cell, direction);
+    }
+
+    /// Returns the cells reachable from `position` in one move.
+    pub fn neighbours(&self, position: Position) -> Vec<Position> {
+        return [
+            CardinalDirection::North,
+            CardinalDirection::East,
+            CardinalDirection::South,
+            CardinalDirection::West,
+        ]
+        .iter()
+        .filter_map(|&direction: &CardinalDirection| self.neighbour(position, direction))
+        .collect();
+    }
+
+    /// Returns the cells where a player or a monster may be placed: all of them but the exit, unless
+    /// the maze has a single cell.
+    pub fn spawn_positions(&self) -> Vec<Position> {
+        let mut positions: Vec<Position> = Vec::with_capacity(self.maze.columns * self.maze.rows);
+        for row in 0..self.maze.rows {
+            for column in 0..self.maze.columns {
+                if (column, row) != self.maze.goal || self.maze.columns * self.maze.rows == 1 {
+                    positions.push((column, row));
+                }
+            }
+        }
+        return positions;
+    }
+
+    /// Picks a random cell which is not the exit, unless the maze has a single cell.
+    pub fn random_position(&self, rng: &mut StdRng) -> Position {
+        loop {
+            let position: Position = (
+                rng.random_range(0..self.maze.columns),
+                rng.random_range(0..self.maze.rows),
+            );
+            if position != self.maze.goal || self.maze.columns * self.maze.rows == 1 {
+                return position;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbour() {
+        let world: World = World::new(3, 2);
+        assert_eq!(world.maze.goal, (2, 1));
+        assert_eq!(world.neighbour((0, 0), CardinalDirection::North), None);
+        assert_eq!(world.neighbour((0, 0), CardinalDirection::West), None);
+        assert_eq!(
+            world.neighbour((0, 0), CardinalDirection::East),
+            Some((1, 0))
+        );
+        assert_eq!(
+            world.neighbour((0, 0), CardinalDirection::South),
+            Some((0, 1))
+        );
+        assert_eq!(world.neighbour((2, 1), CardinalDirection::East), None);
+        assert_eq!(world.neighbour((2, 1), CardinalDirection::South), None);
+        assert_eq!(world.neighbours((0, 0)), vec![(1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_wall() {
+        let world: World = World::new(3, 2);
+        assert_eq!(world.wall((0, 0), CardinalDirection::East), Some(false));
+        assert_eq!(world.wall((0, 0), CardinalDirection::North), Some(true));
+        assert_eq!(world.wall((0, -1), CardinalDirection::South), Some(true));
+        assert_eq!(world.wall((0, -1), CardinalDirection::North), None);
+        assert_eq!(world.wall((2, 1), CardinalDirection::East), Some(true));
+    }
+
+    #[test]
+    fn test_inner_walls() {
+        let mut maze: Maze = Maze::open(3, 2);
+        maze.set_wall((0, 0), CardinalDirection::East, true);
+        let world: World = World::from_maze(maze);
+        assert_eq!(world.neighbour((0, 0), CardinalDirection::East), None);
+        assert_eq!(world.neighbour((1, 0), CardinalDirection::West), None);
+        assert_eq!(world.neighbours((0, 0)), vec![(0, 1)]);
+        assert_eq!(world.wall((1, 0), CardinalDirection::West), Some(true));
+        assert_eq!(world.wall((1, 0), CardinalDirection::South), Some(false));
+    }
+}
@@ -1,9 +1,76 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+use crate::types::action::RelativeDirection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CardinalDirection {
     North,
     East,
     South,
     West,
 }
+
+impl CardinalDirection {
+    /// Returns the direction faced after turning towards a relative direction.
+    pub fn turn(&self, relative_direction: RelativeDirection) -> CardinalDirection {
+        let quarter_turns: usize = match relative_direction {
+            RelativeDirection::Front => 0,
+            RelativeDirection::Right => 1,
+            RelativeDirection::Back => 2,
+            RelativeDirection::Left => 3,
+        };
+        return CardinalDirection::from_quarter_turns(self.quarter_turns() + quarter_turns);
+    }
+
+    /// Returns the angle of the direction in degrees, clockwise from North.
+    pub fn degrees(&self) -> f32 {
+        return self.quarter_turns() as f32 * 90.0;
+    }
+
+    fn quarter_turns(&self) -> usize {
+        match self {
+            CardinalDirection::North => 0,
+            CardinalDirection::East => 1,
+            CardinalDirection::South => 2,
+            CardinalDirection::West => 3,
+        }
+    }
+
+    fn from_quarter_turns(quarter_turns: usize) -> CardinalDirection {
+        match quarter_turns % 4 {
+            0 => CardinalDirection::North,
+            1 => CardinalDirection::East,
+            2 => CardinalDirection::South,
+            _ => CardinalDirection::West,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(
+            CardinalDirection::North.turn(RelativeDirection::Front),
+            CardinalDirection::North
+        );
+        assert_eq!(
+            CardinalDirection::North.turn(RelativeDirection::Right),
+            CardinalDirection::East
+        );
+        assert_eq!(
+            CardinalDirection::West.turn(RelativeDirection::Right),
+            CardinalDirection::North
+        );
+        assert_eq!(
+            CardinalDirection::East.turn(RelativeDirection::Back),
+            CardinalDirection::West
+        );
+        assert_eq!(
+            CardinalDirection::North.turn(RelativeDirection::Left),
+            CardinalDirection::West
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,6 +10,14 @@ pub enum RegistrationError {
     TooManyPlayers,
 }
 
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Player registration failed: {:?}", self);
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ActionError {
     CannotPassThroughWall,
@@ -15,4 +25,9 @@ pub enum ActionError {
     NoRunningChallenge,
     SolveChallengeFirst,
     InvalidChallengeSolution,
+    /// The player attacked a monster without enough teammates around it and was sent back to its spawn.
+    ///
+    /// An extension of the protocol, which has no monsters: only the mini server sends it, when its
+    /// monsters are enabled with `--monsters`. See the README.
+    KilledByMonster,
 }
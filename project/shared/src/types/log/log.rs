@@ -1,19 +1,315 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable holding the most verbose level to print: `error`, `warning`, `info` or `debug`.
+pub const LOG_LEVEL_VARIABLE: &str = "LOG_LEVEL";
+/// The environment variable holding the path of the file where the logs are also written as JSON lines.
+pub const LOG_JSON_FILE_VARIABLE: &str = "LOG_JSON_FILE";
+
+/// The level of a log line, from the most to the least important.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 0,
+    Warning = 1,
+    Info = 2,
+    Debug = 3,
+}
+
+impl Level {
+    /// Returns the prefix printed in the terminal.
+    fn prefix(&self) -> &'static str {
+        return match self {
+            Level::Error => "🔴 ERROR  ",
+            Level::Warning => "🟠 WARNING",
+            Level::Info => "🔵 INFO   ",
+            Level::Debug => "🟣 DEBUG  ",
+        };
+    }
+
+    fn name(&self) -> &'static str {
+        return match self {
+            Level::Error => "ERROR",
+            Level::Warning => "WARNING",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        };
+    }
+
+    fn from_u8(value: u8) -> Level {
+        return match value {
+            0 => Level::Error,
+            1 => Level::Warning,
+            2 => Level::Info,
+            _ => Level::Debug,
+        };
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return match value.trim().to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warning" | "warn" => Ok(Level::Warning),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            _ => Err(format!("Unknown log level: {}", value)),
+        };
+    }
+}
+
+/// The most verbose level printed. The lines of a more verbose level are not even formatted.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+/// The file where the logs are also written as JSON lines, if any.
+static JSON_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+thread_local! {
+    /// The fields added to every line logged by the current thread, e.g. the name of its player.
+    static CONTEXT: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
 pub struct Log;
 
 impl Log {
+    /// Configures the logs from the `LOG_LEVEL` and `LOG_JSON_FILE` environment variables.
+    /// An invalid level is reported and ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON file cannot be opened.
+    pub fn init_from_env() -> io::Result<()> {
+        if let Ok(value) = std::env::var(LOG_LEVEL_VARIABLE) {
+            match value.parse::<Level>() {
+                Ok(level) => Log::set_level(level),
+                Err(e) => Log::write(Level::Warning, format_args!("{}", e)),
+            }
+        }
+
+        if let Ok(path) = std::env::var(LOG_JSON_FILE_VARIABLE) {
+            Log::set_json_file(&path)?;
+        }
+
+        return Ok(());
+    }
+
+    /// Sets the most verbose level printed. It can be changed at any time.
+    pub fn set_level(level: Level) {
+        MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn level() -> Level {
+        return Level::from_u8(MAX_LEVEL.load(Ordering::Relaxed));
+    }
+
+    /// Tells if the lines of a level are printed.
+    #[inline]
+    pub fn enabled(level: Level) -> bool {
+        return level as u8 <= MAX_LEVEL.load(Ordering::Relaxed);
+    }
+
+    /// Also writes the logs as JSON lines, appended to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn set_json_file(path: &str) -> io::Result<()> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        *JSON_FILE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(file);
+        return Ok(());
+    }
+
+    /// Adds a field to every line logged by the current thread, or replaces its value.
+    pub fn set_context(key: &str, value: &str) {
+        CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            match context.iter_mut().find(|(field, _)| field == key) {
+                Some((_, field_value)) => *field_value = String::from(value),
+                None => context.push((String::from(key), String::from(value))),
+            }
+        });
+    }
+
+    /// Removes the fields of the current thread.
+    pub fn clear_context() {
+        CONTEXT.with(|context| context.borrow_mut().clear());
+    }
+
+    /// Writes a line to the terminal, and to the JSON file if there is one.
+    /// The `log_*!` macros only call it when the level is enabled.
+    pub fn write(level: Level, arguments: fmt::Arguments<'_>) {
+        let message: String = fmt::format(arguments);
+        let timestamp: String = format_timestamp(SystemTime::now());
+        let fields: Vec<(String, String)> = CONTEXT.with(|context| context.borrow().clone());
+
+        println!("{}", format_line(&timestamp, level, &fields, &message));
+
+        let mut json_file = JSON_FILE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(file) = json_file.as_mut() {
+            // A log line which cannot be written must not stop the game.
+            let _ = writeln!(file, "{}", json_line(&timestamp, level, &fields, &message));
+        }
+    }
+
+    /// Writes a line like `log_error!`: nothing is written when the level is filtered out.
     pub fn error(message: &str) {
-        println!("🔴 ERROR   {}", message);
+        crate::log_at!(Level::Error, "{}", message);
     }
 
     pub fn warning(message: &str) {
-        println!("🟠 WARNING {}", message);
+        crate::log_at!(Level::Warning, "{}", message);
     }
 
     pub fn info(message: &str) {
-        println!("🔵 INFO    {}", message);
+        crate::log_at!(Level::Info, "{}", message);
     }
 
     pub fn debug(message: &str) {
-        println!("🟣 DEBUG   {}", message);
+        crate::log_at!(Level::Debug, "{}", message);
+    }
+}
+
+/// Formats a line for the terminal: `<timestamp> <level> [<key>=<value> ...] <message>`.
+fn format_line(
+    timestamp: &str,
+    level: Level,
+    fields: &[(String, String)],
+    message: &str,
+) -> String {
+    if fields.is_empty() {
+        return format!("{} {} {}", timestamp, level.prefix(), message);
+    }
+
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    return format!(
+        "{} {} [{}] {}",
+        timestamp,
+        level.prefix(),
+        fields.join(" "),
+        message
+    );
+}
+
+/// Formats a line for the JSON file, the context fields being top level keys.
+fn json_line(timestamp: &str, level: Level, fields: &[(String, String)], message: &str) -> String {
+    let mut object: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    object.insert(String::from("timestamp"), timestamp.into());
+    object.insert(String::from("level"), level.name().into());
+    for (key, value) in fields {
+        object.insert(key.clone(), value.as_str().into());
+    }
+    object.insert(String::from("message"), message.into());
+
+    return serde_json::Value::Object(object).to_string();
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp with milliseconds.
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch: std::time::Duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds: u64 = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day: u64 = seconds % 86_400;
+
+    return format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    );
+}
+
+/// Converts a number of days since 1970-01-01 into a (year, month, day) date.
+/// See Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days: i64 = days + 719_468;
+    let era: i64 = days.div_euclid(146_097);
+    let day_of_era: i64 = days.rem_euclid(146_097);
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month: i64 = (5 * day_of_year + 2) / 153;
+    let day: u32 = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month: u32 = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return (year, month, day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_level() {
+        assert_eq!("debug".parse::<Level>(), Ok(Level::Debug));
+        assert_eq!(" WARNING ".parse::<Level>(), Ok(Level::Warning));
+        assert!("verbose".parse::<Level>().is_err());
+        assert!(Level::Error < Level::Info);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_millis(1_709_210_096_789)),
+            "2024-02-29T12:34:56.789Z"
+        );
+    }
+
+    #[test]
+    fn test_format_line() {
+        let fields: Vec<(String, String)> = vec![(String::from("player"), String::from("P1"))];
+        assert_eq!(
+            format_line("t", Level::Info, &fields, "moved"),
+            "t 🔵 INFO    [player=P1] moved"
+        );
+        assert_eq!(
+            format_line("t", Level::Error, &[], "lost"),
+            "t 🔴 ERROR   lost"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&json_line("t", Level::Debug, &fields, "a \"quote\"")).unwrap();
+        assert_eq!(json["level"], "DEBUG");
+        assert_eq!(json["player"], "P1");
+        assert_eq!(json["message"], "a \"quote\"");
+    }
+
+    #[test]
+    fn test_context_is_per_thread() {
+        Log::set_context("player", "P1");
+        Log::set_context("player", "P2");
+        let fields: Vec<(String, String)> = CONTEXT.with(|context| context.borrow().clone());
+        assert_eq!(fields, vec![(String::from("player"), String::from("P2"))]);
+
+        let other_thread_fields: Vec<(String, String)> =
+            std::thread::spawn(|| CONTEXT.with(|context| context.borrow().clone()))
+                .join()
+                .unwrap();
+        assert!(other_thread_fields.is_empty());
+
+        Log::clear_context();
     }
 }
@@ -6,28 +6,37 @@ macro_rules! log_info {
     // $arg is a variable that will hold the arguments passed to the macro.
     // tt is a token tree. It is a type of macro fragment.
     ($($arg:tt)*) => {
-        // The macro is safe cause it uses the format! macro to format the message.
-        $crate::types::log::log::Log::info(&format!($($arg)*))
+        $crate::log_at!($crate::types::log::log::Level::Info, $($arg)*)
     };
 }
 
 #[macro_export]
 macro_rules! log_warning {
     ($($arg:tt)*) => {
-        $crate::types::log::log::Log::warning(&format!($($arg)*))
+        $crate::log_at!($crate::types::log::log::Level::Warning, $($arg)*)
     };
 }
 
 #[macro_export]
 macro_rules! log_error {
     ($($arg:tt)*) => {
-        $crate::types::log::log::Log::error(&format!($($arg)*))
+        $crate::log_at!($crate::types::log::log::Level::Error, $($arg)*)
     };
 }
 
 #[macro_export]
 macro_rules! log_debug {
     ($($arg:tt)*) => {
-        $crate::types::log::log::Log::debug(&format!($($arg)*))
+        $crate::log_at!($crate::types::log::log::Level::Debug, $($arg)*)
+    };
+}
+
+/// Logs a line at a level. The arguments are only formatted if the level is enabled.
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::types::log::log::Log::enabled($level) {
+            $crate::types::log::log::Log::write($level, format_args!($($arg)*))
+        }
     };
 }
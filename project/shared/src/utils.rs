@@ -1,5 +1,7 @@
 use crate::{
-    log_debug, log_error, log_warning,
+    log_debug, log_error, log_info, log_warning,
+    types::challenge::Challenge,
+    types::hint::Hint,
     types::message::{
         GameMessage, RegisterTeam, RegisterTeamResult, SubscribePlayer, SubscribePlayerResult,
     },
@@ -7,6 +9,9 @@ use crate::{
 use std::io::{self};
 use std::net::TcpStream;
 
+/// The Base64 character set used by the game.
+const BASE64_TABLE: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+
 /// Decodes a Base64-encoded string into a vector of bytes.
 ///
 /// # Arguments
@@ -21,10 +26,6 @@ use std::net::TcpStream;
 ///
 /// Returns an error if the input contains invalid characters or has an incorrect length.
 pub fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
-    // Define the Base64 character set.
-    const BASE64_TABLE: &[u8; 64] =
-        b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
-
     // Creates an array of 128 elements with a default value of 255.
     // Default value of 255 means "invalid character".
     let mut lookup_table: [u8; 128] = [255u8; 128];
@@ -90,6 +91,34 @@ pub fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
     return Ok(decoded_bytes);
 }
 
+/// Encodes bytes with the Base64 character set of the game, without padding.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode.
+///
+/// # Returns
+///
+/// The encoded string, which `decode_base64` turns back into `bytes`.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded: String = String::new();
+
+    for chunk in bytes.chunks(3) {
+        let first_byte: u32 = chunk[0] as u32;
+        let second_byte: u32 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let third_byte: u32 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let group: u32 = (first_byte << 16) | (second_byte << 8) | third_byte;
+
+        // A chunk of n bytes is written with n + 1 characters.
+        for i in 0..=chunk.len() {
+            let index: usize = ((group >> (18 - 6 * i)) & 0x3F) as usize;
+            encoded.push(BASE64_TABLE[index] as char);
+        }
+    }
+
+    return encoded;
+}
+
 /// Connects to the server and returns the TCP stream.
 ///
 /// # Arguments
@@ -144,7 +173,7 @@ pub fn register_team(stream: &mut TcpStream, team_name: &String) -> io::Result<S
         }
         GameMessage::RegisterTeamResult(RegisterTeamResult::Err(e)) => {
             log_error!("Registration failed: {:?}", e);
-            Err(io::Error::new(io::ErrorKind::Other, "Registration failed"))
+            Err(io::Error::other("Registration failed"))
         }
         _ => {
             log_warning!("Unexpected server response");
@@ -156,7 +185,16 @@ pub fn register_team(stream: &mut TcpStream, team_name: &String) -> io::Result<S
     }
 }
 
-/// Registers a player by using the registration token.
+/// What the server sends once a player is subscribed.
+#[derive(Debug)]
+pub enum SessionStart {
+    /// The encoded radar view of the player.
+    RadarView(String),
+    /// The challenge running when the player resumed its session, which pauses the radar views.
+    Challenge(Challenge),
+}
+
+/// Registers a new player by using the registration token.
 ///
 /// # Arguments
 ///
@@ -165,7 +203,7 @@ pub fn register_team(stream: &mut TcpStream, team_name: &String) -> io::Result<S
 ///
 /// # Returns
 ///
-/// An empty `std::io::Result`.
+/// The encoded first radar view of the player.
 ///
 /// # Errors
 ///
@@ -175,6 +213,43 @@ pub fn register_player(
     registration_token: &str,
     player_name: &str,
 ) -> io::Result<String> {
+    let (session_start, hints) = subscribe_player(stream, registration_token, player_name)?;
+    for hint in hints {
+        log_info!("Hint received with the first RadarView: {:?}", hint);
+    }
+    return match session_start {
+        SessionStart::RadarView(encoded_radar) => Ok(encoded_radar),
+        // A new player has never moved, so it cannot have a challenge.
+        SessionStart::Challenge(challenge) => {
+            log_warning!("Unexpected challenge instead of RadarView: {:?}", challenge);
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unexpected server response",
+            ))
+        }
+    };
+}
+
+/// Subscribes a player by using the registration token: registers a new player, or resumes the session of
+/// a player which lost its connection.
+///
+/// # Arguments
+///
+/// * `stream` - A mutable reference to the TCP stream.
+/// * `registration_token` - The registration token of the team.
+///
+/// # Returns
+///
+/// The radar view of the player, or the challenge which replaces it, then the hints sent before it.
+///
+/// # Errors
+///
+/// Returns an error if the subscription has failed or the server response is unexpected.
+pub fn subscribe_player(
+    stream: &mut TcpStream,
+    registration_token: &str,
+    player_name: &str,
+) -> io::Result<(SessionStart, Vec<Hint>)> {
     let subscribe_player: SubscribePlayer = SubscribePlayer {
         name: String::from(player_name),
         registration_token: registration_token.to_string(),
@@ -185,36 +260,46 @@ pub fn register_player(
 
     match GameMessage::receive(stream)? {
         GameMessage::SubscribePlayerResult(SubscribePlayerResult::Ok) => {
-            match GameMessage::receive(stream) {
-                Ok(GameMessage::RadarView(encoded_radar)) => {
-                    return Ok(encoded_radar);
-                }
-                Ok(other_message) => {
-                    log_warning!(
-                        "Unexpected message instead of RadarView: {:?}",
-                        other_message
-                    );
+            // A hint may be sent with the first RadarView.
+            let mut hints: Vec<Hint> = Vec::new();
+            loop {
+                match GameMessage::receive(stream) {
+                    Ok(GameMessage::RadarView(encoded_radar)) => {
+                        return Ok((SessionStart::RadarView(encoded_radar), hints));
+                    }
+                    Ok(GameMessage::Challenge(challenge)) => {
+                        return Ok((SessionStart::Challenge(challenge), hints));
+                    }
+                    Ok(GameMessage::Hint(hint)) => hints.push(hint),
+                    Ok(other_message) => {
+                        log_warning!(
+                            "Unexpected message instead of RadarView: {:?}",
+                            other_message
+                        );
 
-                    let error: std::io::Error =
-                        io::Error::new(io::ErrorKind::InvalidData, "Unexpected server response");
+                        let error: std::io::Error = io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "Unexpected server response",
+                        );
 
-                    return Err(error);
-                }
-                Err(err) => {
-                    log_error!("Failed to receive first RadarView: {}", err);
+                        return Err(error);
+                    }
+                    Err(err) => {
+                        log_error!("Failed to receive first RadarView: {}", err);
 
-                    let error: std::io::Error =
-                        io::Error::new(io::ErrorKind::Other, "Failed to receive RadarView");
+                        let error: std::io::Error =
+                            io::Error::new(err.kind(), "Failed to receive RadarView");
 
-                    return Err(error);
+                        return Err(error);
+                    }
                 }
             }
         }
         GameMessage::SubscribePlayerResult(SubscribePlayerResult::Err(e)) => {
             log_error!("Player registration failed: {:?}", e);
 
-            let error: std::io::Error =
-                io::Error::new(io::ErrorKind::Other, "Player registration failed");
+            // The refusal is kept so that the caller can tell why.
+            let error: std::io::Error = io::Error::other(e);
 
             return Err(error);
         }
@@ -243,7 +328,7 @@ pub fn print_string_matrix(matrix_name: &str, matrix: &Vec<Vec<String>>) {
 
 #[cfg(test)]
 mod tests {
-    use super::decode_base64;
+    use super::{decode_base64, encode_base64};
 
     #[test]
     fn test_base64_decode_valid() {
@@ -256,6 +341,17 @@ mod tests {
         assert_eq!(decoded, expected);
     }
 
+    #[test]
+    fn test_base64_encode() {
+        let bytes: Vec<u8> = decode_base64("ieysGjGO8papd/a").unwrap();
+        assert_eq!(encode_base64(&bytes), "ieysGjGO8papd/a");
+        assert_eq!(encode_base64(&[]), "");
+        for length in 1..8 {
+            let bytes: Vec<u8> = (0..length).map(|i: u8| i.wrapping_mul(97)).collect();
+            assert_eq!(decode_base64(&encode_base64(&bytes)).unwrap(), bytes);
+        }
+    }
+
     #[test]
     fn test_base64_invalid_chars() {
         let encoded1: &str = "abc!";